@@ -0,0 +1,170 @@
+//! `ruler.toml`: project defaults for the source and target directories and
+//! the `--include`/`--exclude` filters.
+//!
+//! ```toml
+//! exclude = ["*.draft.mdc"]
+//!
+//! [c2g]
+//! from = ".ai/rules"
+//! to = "docs/instructions"
+//! ```
+//!
+//! Top-level keys apply to every mode; a table named after a mode overrides
+//! them for that mode. Command-line flags override both.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, RulerError};
+
+/// File name looked up in the current directory and each of its parents.
+pub const CONFIG_FILE_NAME: &str = "ruler.toml";
+
+/// Defaults that a `ruler.toml` supplies for one mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModeDefaults {
+    /// Source directory, relative to the config file.
+    pub from: Option<PathBuf>,
+    /// Target directory, relative to the config file.
+    pub to: Option<PathBuf>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+/// A parsed `ruler.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub from: Option<PathBuf>,
+    #[serde(default)]
+    pub to: Option<PathBuf>,
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+    #[serde(default)]
+    pub exclude: Option<Vec<String>>,
+    /// Per-mode tables, keyed by mode name such as `c2g`.
+    #[serde(flatten)]
+    pub modes: BTreeMap<String, ModeDefaults>,
+}
+
+impl Config {
+    /// Reads a config file, resolving its relative `from`/`to` paths against
+    /// the directory that contains it.
+    pub fn load(path: &Path) -> Result<Config> {
+        let content = fs::read_to_string(path).map_err(|source| RulerError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut config: Config = toml::from_str(&content).map_err(|source| RulerError::Config {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        let resolve = |dir: &mut Option<PathBuf>| {
+            if let Some(dir) = dir {
+                *dir = base.join(&*dir);
+            }
+        };
+        resolve(&mut config.from);
+        resolve(&mut config.to);
+        for mode in config.modes.values_mut() {
+            resolve(&mut mode.from);
+            resolve(&mut mode.to);
+        }
+        Ok(config)
+    }
+
+    /// Fails on the first per-mode table whose name `is_mode` rejects, so a
+    /// misspelled `[c2gg]` isn't silently ignored. `path` is the file the
+    /// config was loaded from.
+    pub fn check_modes(&self, path: &Path, is_mode: impl Fn(&str) -> bool) -> Result<()> {
+        match self.modes.keys().find(|name| !is_mode(name)) {
+            Some(section) => Err(RulerError::ConfigSection {
+                path: path.to_path_buf(),
+                section: section.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The defaults for `mode`: its table's values, falling back to the
+    /// top-level ones.
+    pub fn for_mode(&self, mode: &str) -> ModeDefaults {
+        let table = self.modes.get(mode).cloned().unwrap_or_default();
+        ModeDefaults {
+            from: table.from.or_else(|| self.from.clone()),
+            to: table.to.or_else(|| self.to.clone()),
+            include: table.include.or_else(|| self.include.clone()),
+            exclude: table.exclude.or_else(|| self.exclude.clone()),
+        }
+    }
+}
+
+/// Returns the nearest `ruler.toml` in `start` or one of its ancestors.
+pub fn find_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_config, Config};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_mode_table_overrides_top_level_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(
+            root.join("ruler.toml"),
+            "to = \"out\"\nexclude = [\"*.draft.mdc\"]\n\n[c2g]\nfrom = \"rules\"\nexclude = []\n",
+        )
+        .unwrap();
+
+        let path = find_config(&root.join("a/b")).unwrap();
+        assert_eq!(path, root.join("ruler.toml"));
+        let config = Config::load(&path).unwrap();
+
+        let c2g = config.for_mode("c2g");
+        assert_eq!(c2g.from, Some(root.join("rules")));
+        assert_eq!(c2g.to, Some(root.join("out")));
+        assert_eq!(c2g.exclude, Some(Vec::new()));
+
+        let g2c = config.for_mode("g2c");
+        assert_eq!(g2c.from, None);
+        assert_eq!(g2c.exclude, Some(vec!["*.draft.mdc".to_string()]));
+        assert_eq!(g2c.include, None);
+    }
+
+    #[test]
+    fn test_unknown_key_is_an_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("ruler.toml");
+        fs::write(&path, "[c2g]\nsource = \"rules\"\n").unwrap();
+        let error = Config::load(&path).unwrap_err().to_string();
+        assert!(error.contains(&PathBuf::from(&path).display().to_string()));
+    }
+
+    #[test]
+    fn test_unknown_mode_table_is_an_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("ruler.toml");
+        fs::write(&path, "[c2g]\nfrom = \"rules\"\n\n[c2gg]\nto = \"out\"\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        let is_mode = |name: &str| ["c2g", "g2c"].contains(&name);
+        let error = config.check_modes(&path, is_mode).unwrap_err().to_string();
+        assert!(error.contains("`[c2gg]`"), "{}", error);
+
+        fs::write(&path, "[c2g]\nfrom = \"rules\"\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert!(config.check_modes(&path, is_mode).is_ok());
+    }
+}
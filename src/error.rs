@@ -0,0 +1,244 @@
+//! Typed errors returned by the conversion functions.
+
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Result type used throughout the `parser` module.
+pub type Result<T, E = RulerError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while discovering, converting or writing rules.
+#[derive(Debug, Error)]
+pub enum RulerError {
+    /// A source, target or included file could not be read.
+    #[error("Failed to read file: {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A source file is not valid UTF-8, so it is skipped rather than converted.
+    #[error("File is not valid UTF-8 (invalid byte at offset {offset}), skipping: {}", path.display())]
+    NotUtf8 { path: PathBuf, offset: usize },
+
+    /// A target file could not be written.
+    #[error("Failed to write file: {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// `init`: the file to scaffold exists and `--force` was not given.
+    #[error("{} already exists; use --force to overwrite it", .0.display())]
+    AlreadyExists(PathBuf),
+
+    /// `--prune`: an orphaned target could not be deleted.
+    #[error("Failed to remove file: {}", path.display())]
+    Remove {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A target directory could not be created.
+    #[error("Failed to create directory: {}", path.display())]
+    CreateDir {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The directory to convert from does not exist.
+    #[error("source directory does not exist: {}", .0.display())]
+    SourceDirMissing(PathBuf),
+
+    /// A `--from` archive could not be unpacked.
+    #[error("Failed to unpack archive: {}", path.display())]
+    Archive {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A `_defaults.mdc` uses YAML frontmatter and an inheriting rule TOML, or
+    /// the other way around.
+    #[error("{} uses a different frontmatter format than the rules inheriting from it", .0.display())]
+    DefaultsFormat(PathBuf),
+
+    /// Walking a source directory failed.
+    #[error("Failed to read directory entry")]
+    Walk(#[from] ignore::Error),
+
+    /// A file found while walking was not under the directory being walked.
+    #[error("Failed to get relative path: {}", .0.display())]
+    RelativePath(PathBuf),
+
+    /// Frontmatter was not valid YAML/TOML or did not match the expected fields.
+    /// `kind` names the format being read, e.g. `Cursor` or `GitHub`.
+    #[error("Failed to parse {kind} frontmatter")]
+    Parse {
+        kind: &'static str,
+        #[source]
+        source: FrontmatterError,
+    },
+
+    /// Converted metadata could not be serialized.
+    #[error("Failed to serialize {kind} metadata")]
+    Serialize {
+        kind: &'static str,
+        #[source]
+        source: FrontmatterError,
+    },
+
+    /// Several sources map to the same target path; none of them is written.
+    #[error(
+        "{} sources map to {}: {}",
+        sources.len(),
+        target.display(),
+        sources.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    TargetCollision {
+        target: PathBuf,
+        sources: Vec<PathBuf>,
+    },
+
+    /// An error attributed to one rule file.
+    #[error("Failed to process rule: {}", path.display())]
+    Rule {
+        path: PathBuf,
+        #[source]
+        source: Box<RulerError>,
+    },
+
+    /// `@include` directives formed a cycle; holds the full chain.
+    #[error("Include cycle detected: {}", .0.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(" -> "))]
+    IncludeCycle(Vec<PathBuf>),
+
+    /// A glob pattern could not be compiled.
+    #[error("Invalid glob `{glob}`")]
+    InvalidGlob {
+        glob: String,
+        #[source]
+        source: globset::Error,
+    },
+
+    /// `--strict`: an `alwaysApply: true` rule also lists globs, which the
+    /// conversion would drop.
+    #[error(
+        "`alwaysApply: true` overrides `globs`; remove {} or set `alwaysApply: false`",
+        .0.iter().map(|g| format!("`{}`", g)).collect::<Vec<_>>().join(", ")
+    )]
+    AlwaysApplyWithGlobs(Vec<String>),
+
+    /// The combined matcher for a set of valid globs could not be built.
+    #[error("Failed to build glob matcher")]
+    GlobSet(#[from] globset::Error),
+
+    /// `--expand-env` under `--strict`: a body refers to a variable that isn't set.
+    #[error("Environment variable `{0}` is not set")]
+    UndefinedVariable(String),
+
+    /// A glob starts with `/` while `--no-absolute-globs` is set.
+    #[error("Absolute glob pattern `{0}` is not repo-relative")]
+    AbsoluteGlob(String),
+
+    /// `--always-apply-glob` is empty or only whitespace.
+    #[error("--always-apply-glob needs a pattern, such as `**/*`")]
+    EmptyAlwaysApplyGlob,
+
+    /// `--target-extension` is not a file name suffix starting with a dot.
+    #[error("Invalid target extension `{0}`: expected a suffix starting with `.`, such as `.md`")]
+    TargetExtension(String),
+
+    /// `--check`: the target does not exist.
+    #[error("{} is missing", .0.display())]
+    MissingTarget(PathBuf),
+
+    /// `--check`: the target differs from the converted output.
+    #[error("{} is out of date:\n{diff}", path.display())]
+    OutdatedTarget { path: PathBuf, diff: String },
+
+    /// `--check` found missing or out-of-date targets for this many sources.
+    #[error("Check failed: {0} source file(s) have a missing or out-of-date target")]
+    CheckFailed(usize),
+
+    /// `--verify-complete` found sources without a target on disk.
+    #[error(
+        "Verification failed: {} source file(s) have no target:\n{}",
+        .0.len(),
+        .0.iter().map(|(s, t)| format!("  {} -> {}", s.display(), t.display())).collect::<Vec<_>>().join("\n")
+    )]
+    Incomplete(Vec<(PathBuf, PathBuf)>),
+
+    /// `--strict`: a source failed or warned, so the run was aborted.
+    #[error("Aborted by --strict: {errors} error(s) and {warnings} warning(s)")]
+    Strict { errors: usize, warnings: usize },
+
+    /// This many sources failed to convert; the others were still written.
+    #[error("{0} source file(s) failed to convert")]
+    Failed(usize),
+
+    /// `--fail-on-warning`: the run emitted this many warnings.
+    #[error("{0} warning(s) emitted with --fail-on-warning")]
+    Warnings(usize),
+
+    /// A `--changelog-against` manifest is not a `--format json` report.
+    #[error("Failed to parse manifest: {}", path.display())]
+    Manifest {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A `ruler.toml` is not valid TOML or has unknown keys.
+    #[error("Failed to parse config file: {}", path.display())]
+    Config {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// A `ruler.toml` has a table that isn't named after a mode, such as a
+    /// misspelled `[c2gg]`.
+    #[error("Unknown section `[{section}]` in config file {}: expected a mode such as `[c2g]`", path.display())]
+    ConfigSection { path: PathBuf, section: String },
+
+    /// A rule bundle has text before its first rule, or a rule without a
+    /// `name` or with the same name as another.
+    #[error("Invalid rule bundle: {0}")]
+    Bundle(String),
+
+    /// A `--rename-map` file is malformed or maps a source to a path instead
+    /// of a file name.
+    #[error("Invalid rename map {}: {message}", path.display())]
+    RenameMap { path: PathBuf, message: String },
+
+    /// `--since`: git could not list the files changed since the ref.
+    #[error("Failed to list files changed since `{reference}`: {message}")]
+    Git { reference: String, message: String },
+
+    /// `--watch` could not watch the source directory.
+    #[error("Failed to watch for file changes")]
+    Watch(#[from] notify::Error),
+
+    #[error("Failed to build thread pool")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    #[error("Failed to serialize JSON report")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The underlying YAML or TOML error behind [`RulerError::Parse`] and
+/// [`RulerError::Serialize`].
+#[derive(Debug, Error)]
+pub enum FrontmatterError {
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    TomlDe(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSer(#[from] toml::ser::Error),
+}
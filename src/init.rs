@@ -0,0 +1,106 @@
+//! `ruler init`: the directory layout the conversions expect, with a sample
+//! rule to start from.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, RulerError};
+
+/// Directories created under the project root.
+pub const SCAFFOLD_DIRS: [&str; 2] = [".cursor/rules", ".github/instructions"];
+
+/// Path of the sample rule, relative to the project root.
+pub const EXAMPLE_RULE: &str = ".cursor/rules/example.mdc";
+
+const EXAMPLE_RULE_CONTENT: &str = r#"---
+# Shown to the agent, which decides from it whether the rule is relevant.
+# `c2g` keeps it as the instruction's description.
+description: "Example rule: coding conventions for TypeScript files"
+# The rule is attached when a file matching one of these patterns is in
+# context. `c2g` joins them into GitHub Copilot's `applyTo`.
+globs: ["*.ts", "src/**/*.tsx"]
+# `true` attaches the rule to every request regardless of globs, which `c2g`
+# writes as `applyTo: "**"`.
+alwaysApply: false
+---
+
+# Example rule
+
+Replace this text with the guidance the assistant should follow, then run
+`ruler c2g` to write it to `.github/instructions/example.instructions.md`.
+
+- Prefer `const` over `let`.
+- Give exported functions explicit return types.
+"#;
+
+/// Creates the Cursor and GitHub Copilot directories under `root` and writes
+/// the sample rule, returning the paths created. Existing directories are
+/// kept; an existing sample rule is an error unless `force` is set, in which
+/// case it is replaced.
+pub fn init_project(root: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let example = root.join(EXAMPLE_RULE);
+    if example.exists() && !force {
+        return Err(RulerError::AlreadyExists(example));
+    }
+
+    let mut created = Vec::new();
+    for dir in SCAFFOLD_DIRS {
+        let dir = root.join(dir);
+        if !dir.is_dir() {
+            fs::create_dir_all(&dir).map_err(|source| RulerError::CreateDir {
+                path: dir.clone(),
+                source,
+            })?;
+            created.push(dir);
+        }
+    }
+    fs::write(&example, EXAMPLE_RULE_CONTENT).map_err(|source| RulerError::Write {
+        path: example.clone(),
+        source,
+    })?;
+    created.push(example);
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{init_project, EXAMPLE_RULE};
+    use crate::parser::convert_cursor_content;
+    use std::fs;
+
+    #[test]
+    fn test_init_scaffolds_once_unless_forced() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+
+        let created = init_project(root, false).unwrap();
+        assert_eq!(
+            created,
+            vec![
+                root.join(".cursor/rules"),
+                root.join(".github/instructions"),
+                root.join(EXAMPLE_RULE)
+            ]
+        );
+        let example = fs::read_to_string(root.join(EXAMPLE_RULE)).unwrap();
+        let github = convert_cursor_content(&example).unwrap();
+        assert!(github.contains("applyTo: \"*.ts,src/**/*.tsx\""));
+
+        fs::write(root.join(EXAMPLE_RULE), "Edited.").unwrap();
+        let err = init_project(root, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(
+            fs::read_to_string(root.join(EXAMPLE_RULE)).unwrap(),
+            "Edited."
+        );
+
+        assert_eq!(
+            init_project(root, true).unwrap(),
+            vec![root.join(EXAMPLE_RULE)]
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(EXAMPLE_RULE)).unwrap(),
+            example
+        );
+    }
+}
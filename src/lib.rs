@@ -0,0 +1,15 @@
+//! Conversion between Cursor rules and GitHub Copilot instructions.
+//!
+//! The `parser` module exposes both directory-level conversions and
+//! content-level helpers that work on in-memory strings. The `serve` module
+//! previews conversions over a small local HTTP server, `config` reads the
+//! project defaults in `ruler.toml`, and `init` scaffolds a new project. Conversion functions return
+//! [`RulerError`], whose variants callers can match on.
+
+pub mod config;
+pub mod error;
+pub mod init;
+pub mod parser;
+pub mod serve;
+
+pub use error::RulerError;
@@ -1,10 +1,27 @@
-use anyhow::Result;
-use clap::{Parser, ValueEnum};
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 
-mod parser;
-
-use parser::{convert_cursor_to_github, convert_github_to_cursor};
+use ruler::config::{find_config, Config};
+use ruler::init::init_project;
+use ruler::parser::archive::{is_archive, unpack_archive};
+use ruler::parser::common::{
+    resolve_source_dir, with_source_body, with_trailing_newline, ALTERNATE_RULE_ROOTS,
+};
+use ruler::parser::coverage::{compute_coverage, render_coverage};
+use ruler::parser::env::expand_env;
+use ruler::parser::list::{list_rules, render_rule_list};
+use ruler::parser::registry::{find_converter, CURSOR_RULES_DIR};
+use ruler::parser::rename::read_rename_map;
+use ruler::parser::stats::{compute_stats, render_stats};
+use ruler::parser::upgrade::upgrade_globs;
+use ruler::parser::{
+    watch_cursor_to_github, ColorChoice, ConvertOptions, Eol, FrontmatterFormat, OutputFormat,
+    OverwritePolicy,
+};
+use ruler::serve::serve;
 
 #[derive(Parser)]
 #[command(name = "ruler")]
@@ -15,13 +32,225 @@ struct Cli {
     #[arg(value_enum)]
     mode: ConversionMode,
 
-    /// Source directory (defaults: c2g=.cursor/rules, g2c=.github/instructions)
+    /// diff: the conversion to preview, e.g. `ruler diff c2g`
+    #[arg(value_enum)]
+    diff_mode: Option<ConversionMode>,
+
+    /// Source directory (defaults: c2g/c2w/c2cl/c2g-merge/c2claude/c2zed=.cursor/rules,
+    /// g2c=.github/instructions, w2c=.windsurf/rules, cl2c=.clinerules); g2c-split,
+    /// claude2c and zed2c read a file (defaults: .github/copilot-instructions.md, CLAUDE.md,
+    /// .rules)
     #[arg(short = 'f', long = "from")]
     from_folder: Option<PathBuf>,
 
-    /// Target directory (defaults: c2g=.github/instructions, g2c=.cursor/rules)
+    /// Target directory (defaults: c2g=.github/instructions, c2w=.windsurf/rules,
+    /// c2cl=.clinerules, g2c/w2c/cl2c/g2c-split/claude2c/zed2c=.cursor/rules); c2g accepts
+    /// it repeatedly to write every converted file into each directory, and c2g-merge,
+    /// c2claude and c2zed write a file (defaults: .github/copilot-instructions.md,
+    /// CLAUDE.md, .rules)
     #[arg(short = 't', long = "to")]
-    to_folder: Option<PathBuf>,
+    to_folder: Vec<PathBuf>,
+
+    /// c2g: write one instruction file per glob (`rule.<glob-slug>.instructions.md`)
+    #[arg(long)]
+    one_glob_per_file: bool,
+
+    /// c2g: merge rules into one `<language>.instructions.md` per language found in their globs
+    #[arg(long, conflicts_with = "one_glob_per_file")]
+    by_language: bool,
+
+    /// c2g --by-language: wrap each merged rule in a collapsible `<details>` block
+    #[arg(long, requires = "by_language")]
+    collapsible_sections: bool,
+
+    /// g2c: write every instruction as a section of this one Markdown file instead of a rule each
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["to_folder", "prune"])]
+    output_single: Option<PathBuf>,
+
+    /// After converting, fail if any source file did not produce a target
+    #[arg(long)]
+    verify_complete: bool,
+
+    /// Expand `@include <path>` lines in rule bodies before converting
+    #[arg(long)]
+    expand_includes: bool,
+
+    /// Replace `${VAR}` in rule bodies with the environment variable's value
+    #[arg(long)]
+    expand_env: bool,
+
+    /// Convert only the frontmatter, writing each rule's body byte for byte as in its source
+    #[arg(
+        long,
+        conflicts_with_all = ["expand_includes", "expand_env", "by_language", "no_frontmatter", "template"]
+    )]
+    frontmatter_only: bool,
+
+    /// Don't skip files matched by `.gitignore` when discovering sources
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Follow symlinked files and directories when discovering sources
+    #[arg(long)]
+    follow_symlinks: bool,
+
+    /// Only discover sources at most N directory levels below the source directory (1: no subdirectories)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Write all targets directly into the target directory, without the source's subdirectories
+    #[arg(long)]
+    flatten: bool,
+
+    /// Only convert sources matching this glob, relative to the source directory (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip sources matching this glob, relative to the source directory (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Only convert sources git reports as changed since this ref (commit, branch or tag)
+    #[arg(long, value_name = "REF")]
+    since: Option<String>,
+
+    /// c2g: description for rules without one; `{name}` and `{path}` are substituted
+    #[arg(long, value_name = "TEMPLATE")]
+    default_description: Option<String>,
+
+    /// c2g: take a missing description from the body's first `# ` heading
+    #[arg(long)]
+    infer_description: bool,
+
+    /// c2g/g2c: file name suffix of GitHub instructions, e.g. `.md` (default `.instructions.md`)
+    #[arg(long, value_name = "SUFFIX")]
+    target_extension: Option<String>,
+
+    /// c2g/g2c: `applyTo` pattern that means "every file", e.g. `**/*` (default `**`)
+    #[arg(long, value_name = "GLOB")]
+    always_apply_glob: Option<String>,
+
+    /// Lowercase, deduplicate and sort rule tags on conversion
+    #[arg(long)]
+    normalize_tags: bool,
+
+    /// Fail rules with a glob starting with `/` (by default they only warn)
+    #[arg(long)]
+    no_absolute_globs: bool,
+
+    /// Fail rules with a glob pattern that doesn't compile (by default they only warn)
+    #[arg(long)]
+    strict: bool,
+
+    /// Exit with a non-zero status after the run if any warning was emitted
+    #[arg(long)]
+    fail_on_warning: bool,
+
+    /// Copy each target to `<target>.bak` before overwriting it with different contents
+    #[arg(long)]
+    backup: bool,
+
+    /// Whether existing targets are replaced: always, never, or only when the source is newer
+    #[arg(long, value_enum, default_value_t = OverwritePolicy::Always)]
+    overwrite: OverwritePolicy,
+
+    /// Delete the targets the previous --manifest recorded for sources that are gone or renamed
+    #[arg(long, conflicts_with = "since", requires = "manifest")]
+    prune: bool,
+
+    /// Don't write anything; fail with a diff if any target is missing or out of date
+    #[arg(long)]
+    check: bool,
+
+    /// c2g: keep running and re-convert each source file when it changes
+    #[arg(long, conflicts_with_all = ["check", "stdin"])]
+    watch: bool,
+
+    /// upgrade-globs: list the rules that would be rewritten without changing them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Frontmatter syntax to write (default: keep each source's `---` YAML or `+++` TOML)
+    #[arg(long, value_enum)]
+    frontmatter_format: Option<FrontmatterFormat>,
+
+    /// Print a Markdown changelog of added, updated and removed targets
+    /// against a report saved earlier with `--format json`
+    #[arg(long, value_name = "MANIFEST")]
+    changelog_against: Option<PathBuf>,
+
+    /// Write a JSON manifest of every source, target, status and content digest,
+    /// with the mode and a timestamp, to this file after the run
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<PathBuf>,
+
+    /// Color progress, warning and error lines (auto: only on a terminal)
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Line endings of written targets: `keep` the source's, or force `lf` or `crlf`
+    #[arg(long, value_enum, default_value_t = Eol::Keep)]
+    eol: Eol,
+
+    /// Output format for progress and results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Number of files to convert in parallel (default: number of cores)
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+
+    /// Roots to try, in order, when the default Cursor rules directory is absent
+    /// (default: .ai/rules, .rules)
+    #[arg(long = "search-root", value_name = "DIR")]
+    search_roots: Vec<PathBuf>,
+
+    /// coverage: project tree to match rule globs against; init: where to scaffold
+    #[arg(long, default_value = ".")]
+    project_root: PathBuf,
+
+    /// init: replace an existing sample rule
+    #[arg(long)]
+    force: bool,
+
+    /// coverage: list every file no rule applies to
+    #[arg(long)]
+    list_uncovered: bool,
+
+    /// serve: port to listen on (127.0.0.1)
+    #[arg(long, default_value_t = 7878)]
+    port: u16,
+
+    /// Convert a single file read from stdin and write the result to stdout
+    #[arg(long)]
+    stdin: bool,
+
+    /// c2g/g2c: TSV or JSON file mapping source paths (relative to the source
+    /// directory) to target file names, overriding the stem-based names
+    #[arg(long, value_name = "FILE")]
+    rename_map: Option<PathBuf>,
+
+    /// c2g: omit the frontmatter and start the body with the rule's name as a
+    /// `#` heading and its description, for tools that don't read frontmatter
+    #[arg(long, conflicts_with = "frontmatter_format")]
+    no_frontmatter: bool,
+
+    /// c2g: file laying out each generated instruction, with `{{frontmatter}}`
+    /// and `{{body}}` placeholders, e.g. to add an "Auto-generated" header
+    #[arg(long, value_name = "FILE")]
+    template: Option<PathBuf>,
+
+    /// Only print the final summary, warnings and errors, not per-file progress
+    #[arg(short = 'q', long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Only print errors and the final summary, followed by the list of failed sources
+    #[arg(long, conflicts_with = "verbose")]
+    report_only_errors: bool,
+
+    /// Log each file's frontmatter, parsed metadata and field mappings to stderr
+    #[arg(short = 'v', long)]
+    verbose: bool,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -30,36 +259,390 @@ enum ConversionMode {
     C2g,
     /// Convert GitHub Copilot instructions to Cursor rules
     G2c,
+    /// Convert Cursor rules to Windsurf rules
+    C2w,
+    /// Convert Windsurf rules to Cursor rules
+    W2c,
+    /// Convert Cursor rules to Cline rules
+    C2cl,
+    /// Convert Cline rules to Cursor rules
+    Cl2c,
+    /// Merge Cursor rules into a single `.github/copilot-instructions.md`
+    C2gMerge,
+    /// Split a `.github/copilot-instructions.md` back into Cursor rules
+    G2cSplit,
+    /// Split a file bundling several `---` separated rules into Cursor rules
+    Bundle2c,
+    /// Split a file bundling several `---` separated rules into GitHub Copilot instructions
+    Bundle2g,
+    /// Concatenate the always-applied Cursor rules into a `CLAUDE.md`
+    C2claude,
+    /// Convert a `CLAUDE.md` into an always-applied Cursor rule
+    Claude2c,
+    /// Concatenate the always-applied Cursor rules into Zed's `.rules`
+    C2zed,
+    /// Convert Zed's `.rules` into an always-applied Cursor rule
+    Zed2c,
+    /// List Cursor rules and how each is activated, without converting
+    List,
+    /// Report how many project files are matched by at least one rule's globs
+    Coverage,
+    /// Count Cursor rules by category (always applied, glob-scoped, ...) without converting
+    Stats,
+    /// Preview Cursor rules beside their GitHub conversion in a local web page
+    Serve,
+    /// Rewrite comma-separated `globs` strings in Cursor rules as arrays, in place
+    UpgradeGlobs,
+    /// Create `.cursor/rules` and `.github/instructions` with a sample rule
+    Init,
+    /// Print a unified diff of what a conversion would change in each target, without writing
+    Diff,
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.verbose {
+        log::set_logger(&STDERR_LOGGER).context("Failed to install logger")?;
+        log::set_max_level(log::LevelFilter::Debug);
+    }
+    // `ruler diff c2g` runs c2g, previewing each target instead of writing it
+    let diff = matches!(cli.mode, ConversionMode::Diff);
+    match cli.diff_mode.take() {
+        Some(ConversionMode::Diff) | None if diff => {
+            usage_error("diff needs the conversion to preview, e.g. `ruler diff c2g`")
+        }
+        Some(mode) if diff => {
+            let name = mode
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default();
+            // Only conversions can be previewed without writing
+            if find_converter(&name).is_none() {
+                usage_error(format!(
+                    "diff previews a conversion such as c2g, which `{}` is not",
+                    name
+                ));
+            }
+            cli.mode = mode;
+        }
+        Some(_) => usage_error("only diff takes a second mode"),
+        None => {}
+    }
+    if diff && (cli.stdin || cli.watch) {
+        usage_error("diff can't be combined with --stdin or --watch");
+    }
+    if diff && cli.format == OutputFormat::Json {
+        usage_error("diff prints unified diffs and can't be combined with --format json");
+    }
+    let mode = cli
+        .mode
+        .to_possible_value()
+        .map(|value| value.get_name().to_string());
+    apply_config(&mut cli, mode.as_deref().unwrap_or_default())?;
+
+    let options = ConvertOptions {
+        format: cli.format,
+        // Convert one file at a time when logging so each file's lines stay together
+        // and, for diff, so the diffs are printed in source order
+        jobs: cli.jobs.or((cli.verbose || diff).then_some(1)),
+        one_glob_per_file: cli.one_glob_per_file,
+        by_language: cli.by_language,
+        collapsible_sections: cli.collapsible_sections,
+        output_single: cli.output_single,
+        verify_complete: cli.verify_complete,
+        expand_includes: cli.expand_includes,
+        expand_env: cli.expand_env,
+        frontmatter_only: cli.frontmatter_only,
+        no_ignore: cli.no_ignore,
+        follow_symlinks: cli.follow_symlinks,
+        max_depth: cli.max_depth,
+        flatten: cli.flatten,
+        include: cli.include,
+        exclude: cli.exclude,
+        since: cli.since,
+        default_description: cli.default_description,
+        infer_description: cli.infer_description,
+        normalize_tags: cli.normalize_tags,
+        frontmatter_format: cli.frontmatter_format,
+        no_absolute_globs: cli.no_absolute_globs,
+        strict: cli.strict,
+        fail_on_warning: cli.fail_on_warning,
+        changelog_against: cli.changelog_against,
+        manifest: cli.manifest,
+        mode,
+        dry_run: cli.dry_run,
+        backup: cli.backup,
+        overwrite: cli.overwrite,
+        prune: cli.prune,
+        check: cli.check || diff,
+        diff,
+        quiet: cli.quiet || cli.report_only_errors,
+        report_only_errors: cli.report_only_errors,
+        target_extension: cli.target_extension,
+        always_apply_glob: cli.always_apply_glob,
+        rename_map: match &cli.rename_map {
+            Some(path) => read_rename_map(path)?,
+            None => Default::default(),
+        },
+        template: cli.template.as_deref().map(read_template).transpose()?,
+        no_frontmatter: cli.no_frontmatter,
+        color: cli.color,
+        eol: cli.eol,
+    };
+    if let Err(e) = options
+        .validate_target_extension()
+        .and_then(|()| options.validate_always_apply_glob())
+    {
+        usage_error(e);
+    }
+
+    if cli.stdin {
+        return convert_stdin(&options);
+    }
+    if cli.watch && !matches!(cli.mode, ConversionMode::C2g) {
+        usage_error("--watch is only supported by c2g");
+    }
+
+    if let Some(converter) = options.mode.as_deref().and_then(find_converter) {
+        let from = if converter.reads_cursor_rules() {
+            cursor_source_dir(cli.from_folder, cli.search_roots, &options)
+        } else {
+            cli.from_folder
+                .or_else(|| converter.default_from().map(PathBuf::from))
+                .unwrap_or_else(|| {
+                    usage_error(format!(
+                        "{} needs its source file given with --from",
+                        converter.name()
+                    ))
+                })
+        };
+        // Kept until the conversion is done, as dropping it removes the files
+        let unpacked = if is_archive(&from) && from.is_file() {
+            if !converter.reads_dir() {
+                usage_error(format!(
+                    "{} can't read rules from an archive",
+                    converter.name()
+                ));
+            }
+            if cli.watch {
+                usage_error("--watch can't be combined with an archive given with --from");
+            }
+            let dir = unpack_archive(&from)?;
+            if options.shows_progress() {
+                println!("Unpacked {} into {}", from.display(), dir.path().display());
+            }
+            Some(dir)
+        } else {
+            None
+        };
+        let from = unpacked
+            .as_ref()
+            .map_or(from, |dir| dir.path().to_path_buf());
+        let mut to_dirs = cli.to_folder;
+        if to_dirs.is_empty() {
+            to_dirs.push(PathBuf::from(converter.default_to()));
+        }
+        if to_dirs.len() > 1 && !converter.multiple_targets() {
+            usage_error("--to can only be given more than once for c2g");
+        }
+        if cli.watch {
+            watch_cursor_to_github(&from, &to_dirs, &options)?;
+        } else {
+            converter.convert_targets(&from, &to_dirs, &options)?;
+        }
+        return Ok(());
+    }
 
     match cli.mode {
-        ConversionMode::C2g => {
-            let from_dir = cli
-                .from_folder
-                .unwrap_or_else(|| PathBuf::from(".cursor/rules"));
-            let to_dir = cli
-                .to_folder
-                .unwrap_or_else(|| PathBuf::from(".github/instructions"));
-            convert_cursor_to_github(&from_dir, &to_dir)
+        ConversionMode::List => {
+            let from_dir = cursor_source_dir(cli.from_folder, cli.search_roots, &options);
+            let rules = list_rules(&from_dir, &options)?;
+            match options.format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&rules)
+                        .context("Failed to serialize rule list")?
+                ),
+                _ => print!("{}", render_rule_list(&rules)),
+            }
+        }
+        ConversionMode::Coverage => {
+            let from_dir = cursor_source_dir(cli.from_folder, cli.search_roots, &options);
+            let report = compute_coverage(&from_dir, &cli.project_root, &options)?;
+            match options.format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report)
+                        .context("Failed to serialize coverage report")?
+                ),
+                _ => print!("{}", render_coverage(&report, cli.list_uncovered)),
+            }
         }
-        ConversionMode::G2c => {
-            let from_dir = cli
-                .from_folder
-                .unwrap_or_else(|| PathBuf::from(".github/instructions"));
-            let to_dir = cli
-                .to_folder
-                .unwrap_or_else(|| PathBuf::from(".cursor/rules"));
-            convert_github_to_cursor(&from_dir, &to_dir)
+        ConversionMode::Stats => {
+            let from_dir = cursor_source_dir(cli.from_folder, cli.search_roots, &options);
+            let stats = compute_stats(&from_dir, &options)?;
+            match options.format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stats)
+                        .context("Failed to serialize rule stats")?
+                ),
+                _ => print!("{}", render_stats(&stats)),
+            }
         }
+        ConversionMode::Serve => {
+            let from_dir = cursor_source_dir(cli.from_folder, cli.search_roots, &options);
+            serve(&from_dir, cli.port, &options)?;
+        }
+        ConversionMode::UpgradeGlobs => {
+            let from_dir = cursor_source_dir(cli.from_folder, cli.search_roots, &options);
+            upgrade_globs(&from_dir, &options)?;
+        }
+        ConversionMode::Diff => unreachable!("diff runs the conversion it previews"),
+        ConversionMode::Init => {
+            for path in init_project(&cli.project_root, cli.force)? {
+                println!("Created: {}", path.display());
+            }
+        }
+        _ => unreachable!("conversion modes run through their converter"),
     }
+    Ok(())
+}
+
+// Fills in the source and target directories and the filters from the
+// nearest `ruler.toml`, for whichever of them weren't given on the command line.
+fn apply_config(cli: &mut Cli, mode: &str) -> Result<()> {
+    let cwd = std::env::current_dir().context("Failed to read current directory")?;
+    let Some(path) = find_config(&cwd) else {
+        return Ok(());
+    };
+    let config = Config::load(&path)?;
+    config.check_modes(&path, |name| ConversionMode::from_str(name, false).is_ok())?;
+    let defaults = config.for_mode(mode);
+    log::info!("using defaults from {}", path.display());
+
+    cli.from_folder = cli.from_folder.take().or(defaults.from);
+    if cli.to_folder.is_empty() {
+        cli.to_folder.extend(defaults.to);
+    }
+    if cli.include.is_empty() {
+        cli.include = defaults.include.unwrap_or_default();
+    }
+    if cli.exclude.is_empty() {
+        cli.exclude = defaults.exclude.unwrap_or_default();
+    }
+    Ok(())
+}
+
+// Reports arguments that parse but can't be used together the way clap
+// reports its own errors: on stderr with the usage line, exiting with code 2.
+// Failed conversions exit with code 1 instead.
+fn usage_error(message: impl std::fmt::Display) -> ! {
+    Cli::command()
+        .error(ErrorKind::ArgumentConflict, message)
+        .exit()
+}
+
+// Reads a `--template` file, which must place the body somewhere.
+fn read_template(path: &Path) -> Result<String> {
+    let template = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template: {}", path.display()))?;
+    if !template.contains("{{body}}") {
+        anyhow::bail!(
+            "Template {} has no {{{{body}}}} placeholder",
+            path.display()
+        );
+    }
+    Ok(template)
+}
+
+// Prints `--verbose` log records to stderr, keeping stdout for results.
+struct StderrLogger;
+
+static STDERR_LOGGER: StderrLogger = StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        eprintln!(
+            "[{}] {}",
+            record.level().as_str().to_lowercase(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+// Resolves the Cursor rules directory, trying the alternate roots when
+// `--from` is absent and `.cursor/rules` does not exist.
+fn cursor_source_dir(
+    from_folder: Option<PathBuf>,
+    search_roots: Vec<PathBuf>,
+    options: &ConvertOptions,
+) -> PathBuf {
+    if let Some(from_dir) = from_folder {
+        return from_dir;
+    }
+    let primary = PathBuf::from(CURSOR_RULES_DIR);
+    let alternates = if search_roots.is_empty() {
+        ALTERNATE_RULE_ROOTS.iter().map(PathBuf::from).collect()
+    } else {
+        search_roots
+    };
+    let from_dir = resolve_source_dir(&primary, &alternates);
+    if from_dir != primary && options.shows_progress() {
+        println!(
+            "{} not found, using rules root {}",
+            primary.display(),
+            from_dir.display()
+        );
+    }
+    from_dir
+}
+
+// Streams a single file through the content-level conversion, ignoring --from/--to.
+fn convert_stdin(options: &ConvertOptions) -> Result<()> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read from stdin")?;
+    if options.expand_env {
+        input = expand_env(&input, options.strict)?;
+    }
+
+    let output = options
+        .mode
+        .as_deref()
+        .and_then(find_converter)
+        .and_then(|converter| converter.convert_content(&input, options))
+        .unwrap_or_else(|| usage_error("--stdin is only supported by conversion modes"))?;
+    let output = options
+        .eol
+        .apply(&with_source_body(&input, output, options));
+    let output = if options.frontmatter_only {
+        output
+    } else {
+        with_trailing_newline(&output)
+    };
+
+    io::stdout()
+        .write_all(output.as_bytes())
+        .context("Failed to write to stdout")?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parser::common::{parse_frontmatter, preprocess_frontmatter, CursorMetadata};
+    use super::ConversionMode;
+    use clap::ValueEnum;
+    use ruler::parser::common::{parse_frontmatter, preprocess_frontmatter, CursorMetadata};
+    use ruler::parser::registry::find_converter;
+    use ruler::parser::{convert_cursor_to_github, convert_github_to_cursor, ConvertOptions};
+    use std::fs;
 
     #[test]
     fn test_parse_frontmatter() {
@@ -151,4 +734,137 @@ This is a test rule with multiple quoted strings format."#;
             "This is a test rule with multiple quoted strings format."
         );
     }
+
+    #[test]
+    fn test_round_trip_preserves_cursor_only_fields() {
+        let temp = tempfile::tempdir().unwrap();
+        let cursor_dir = temp.path().join("cursor");
+        let github_dir = temp.path().join("github");
+        let restored_dir = temp.path().join("restored");
+        fs::create_dir_all(&cursor_dir).unwrap();
+        fs::write(
+            cursor_dir.join("full.mdc"),
+            r#"---
+name: "full-rule"
+description: "Fully populated rule"
+globs: ["*.ts", "*.tsx"]
+alwaysApply: false
+authors: ["Ada", "Grace"]
+tags: ["style", "typescript"]
+version: "1.2.0"
+---
+
+Body text."#,
+        )
+        .unwrap();
+
+        convert_cursor_to_github(&cursor_dir, &github_dir, &ConvertOptions::default()).unwrap();
+        convert_github_to_cursor(&github_dir, &restored_dir, &ConvertOptions::default()).unwrap();
+
+        let restored = fs::read_to_string(restored_dir.join("full.mdc")).unwrap();
+        let (frontmatter, body) = parse_frontmatter(&restored).unwrap();
+        let cursor_meta: CursorMetadata = serde_yaml::from_str(&frontmatter.unwrap()).unwrap();
+        assert_eq!(cursor_meta.name, Some("full-rule".to_string()));
+        assert_eq!(
+            cursor_meta.description,
+            Some("Fully populated rule".to_string())
+        );
+        assert_eq!(
+            cursor_meta.globs,
+            Some(vec!["*.ts".to_string(), "*.tsx".to_string()])
+        );
+        assert_eq!(cursor_meta.always_apply, Some(false));
+        assert_eq!(
+            cursor_meta.authors,
+            Some(vec!["Ada".to_string(), "Grace".to_string()])
+        );
+        assert_eq!(
+            cursor_meta.tags,
+            Some(vec!["style".to_string(), "typescript".to_string()])
+        );
+        assert_eq!(cursor_meta.version, Some("1.2.0".to_string()));
+        assert_eq!(body.trim(), "Body text.");
+    }
+
+    #[test]
+    fn test_round_trip_preserves_priority() {
+        let temp = tempfile::tempdir().unwrap();
+        let cursor_dir = temp.path().join("cursor");
+        let github_dir = temp.path().join("github");
+        let restored_dir = temp.path().join("restored");
+        fs::create_dir_all(&cursor_dir).unwrap();
+        fs::write(
+            cursor_dir.join("ranked.mdc"),
+            "---\ndescription: \"Ranked rule\"\npriority: 10\n---\n\nBody.",
+        )
+        .unwrap();
+
+        convert_cursor_to_github(&cursor_dir, &github_dir, &ConvertOptions::default()).unwrap();
+        let github = fs::read_to_string(github_dir.join("ranked.instructions.md")).unwrap();
+        assert!(github.contains("priority: 10\n"));
+
+        convert_github_to_cursor(&github_dir, &restored_dir, &ConvertOptions::default()).unwrap();
+        let restored = fs::read_to_string(restored_dir.join("ranked.mdc")).unwrap();
+        let (frontmatter, _) = parse_frontmatter(&restored).unwrap();
+        let cursor_meta: CursorMetadata = serde_yaml::from_str(&frontmatter.unwrap()).unwrap();
+        assert_eq!(cursor_meta.priority, Some(10));
+    }
+
+    #[test]
+    fn test_round_trip_normalizes_tags() {
+        let temp = tempfile::tempdir().unwrap();
+        let cursor_dir = temp.path().join("cursor");
+        let github_dir = temp.path().join("github");
+        let restored_dir = temp.path().join("restored");
+        fs::create_dir_all(&cursor_dir).unwrap();
+        fs::write(
+            cursor_dir.join("tagged.mdc"),
+            "---\ntags: [\"Style\", \"rust\", \" style \", \"API\"]\n---\n\nBody.",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            normalize_tags: true,
+            ..Default::default()
+        };
+        convert_cursor_to_github(&cursor_dir, &github_dir, &options).unwrap();
+        let github = fs::read_to_string(github_dir.join("tagged.instructions.md")).unwrap();
+        assert!(github.contains("tags: [\"api\", \"rust\", \"style\"]\n"));
+
+        convert_github_to_cursor(&github_dir, &restored_dir, &options).unwrap();
+        let restored = fs::read_to_string(restored_dir.join("tagged.mdc")).unwrap();
+        let (frontmatter, _) = parse_frontmatter(&restored).unwrap();
+        let cursor_meta: CursorMetadata = serde_yaml::from_str(&frontmatter.unwrap()).unwrap();
+        assert_eq!(
+            cursor_meta.tags,
+            Some(vec![
+                "api".to_string(),
+                "rust".to_string(),
+                "style".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_every_conversion_mode_has_a_converter() {
+        let commands = [
+            "list",
+            "coverage",
+            "stats",
+            "serve",
+            "upgrade-globs",
+            "init",
+            "diff",
+        ];
+        for mode in ConversionMode::value_variants() {
+            let value = mode.to_possible_value().unwrap();
+            let name = value.get_name();
+            assert_eq!(
+                find_converter(name).is_some(),
+                !commands.contains(&name),
+                "{}",
+                name
+            );
+        }
+    }
 }
@@ -0,0 +1,206 @@
+//! Reading rules straight out of a `.zip` or tarball given with `--from`.
+//!
+//! The archive is unpacked into a temporary directory, which is then
+//! converted like any other source directory, so every option applies to
+//! archived rules as well. Entries that would land outside that directory,
+//! such as `../x.mdc`, are not written.
+
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::error::{Result, RulerError};
+
+/// Whether `path` names an archive ruler can unpack, going by its extension:
+/// `.zip`, `.tar`, `.tar.gz` or `.tgz`.
+pub fn is_archive(path: &Path) -> bool {
+    archive_kind(path).is_some()
+}
+
+#[derive(Clone, Copy)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+/// Unpacks the archive at `path` into a new temporary directory, which is
+/// removed when the returned [`TempDir`] is dropped. Entries keep their paths
+/// within the archive.
+pub fn unpack_archive(path: &Path) -> Result<TempDir> {
+    let error = |source: io::Error| RulerError::Archive {
+        path: path.to_path_buf(),
+        source,
+    };
+    let kind = archive_kind(path).ok_or_else(|| {
+        error(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a .zip, .tar, .tar.gz or .tgz file",
+        ))
+    })?;
+    let file = File::open(path).map_err(|source| RulerError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let dir = tempfile::Builder::new()
+        .prefix("ruler-archive-")
+        .tempdir()
+        .map_err(error)?;
+    match kind {
+        ArchiveKind::Zip => unpack_zip(file, dir.path()).map_err(error)?,
+        ArchiveKind::Tar => unpack_tar(file, dir.path()).map_err(error)?,
+        ArchiveKind::TarGz => unpack_tar(GzDecoder::new(file), dir.path()).map_err(error)?,
+    }
+    Ok(dir)
+}
+
+fn unpack_zip(file: File, dir: &Path) -> io::Result<()> {
+    let mut archive = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(io::Error::other)?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let path = dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&path)?)?;
+    }
+    Ok(())
+}
+
+// Only regular files and directories are unpacked: a symlink or hard link could point
+// outside `dir`, which `--follow-symlinks` would then read. Like `unpack_zip`,
+// `unpack_in` skips entries whose path leaves `dir` rather than failing on them.
+fn unpack_tar(reader: impl Read, dir: &Path) -> io::Result<()> {
+    for entry in tar::Archive::new(reader).entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        if kind.is_file() || kind.is_dir() {
+            entry.unpack_in(dir)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_archive, unpack_archive};
+    use crate::parser::c2g::convert_cursor_to_github;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::io::{Cursor, Write};
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_detects_archives_by_extension() {
+        for path in ["rules.zip", "rules.tar", "rules.tar.gz", "RULES.TGZ"] {
+            assert!(is_archive(Path::new(path)), "{}", path);
+        }
+        for path in [".cursor/rules", "rules.mdc", "rules.gz"] {
+            assert!(!is_archive(Path::new(path)), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_converts_rules_from_a_zip() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("pack/style.mdc", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"---\ndescription: Style\nglobs: \"*.rs\"\n---\n\nBe concise.")
+            .unwrap();
+        zip.start_file("../escape.mdc", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"Outside.").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let temp = tempfile::tempdir().unwrap();
+        let archive = temp.path().join("rules.zip");
+        fs::write(&archive, bytes).unwrap();
+        let unpacked = unpack_archive(&archive).unwrap();
+        assert!(!unpacked
+            .path()
+            .parent()
+            .unwrap()
+            .join("escape.mdc")
+            .exists());
+
+        let to_dir = temp.path().join("out");
+        convert_cursor_to_github(unpacked.path(), &to_dir, &ConvertOptions::default()).unwrap();
+        let converted = fs::read_to_string(to_dir.join("pack/style.instructions.md")).unwrap();
+        assert!(converted.contains("applyTo: \"*.rs\""));
+        assert!(converted.contains("Be concise."));
+    }
+
+    #[test]
+    fn test_unpacks_tarballs() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let body = b"Always.";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "rules/always.mdc", &body[..])
+            .unwrap();
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let archive = temp.path().join("rules.tgz");
+        fs::write(&archive, bytes).unwrap();
+        let unpacked = unpack_archive(&archive).unwrap();
+        assert_eq!(
+            fs::read_to_string(unpacked.path().join("rules/always.mdc")).unwrap(),
+            "Always."
+        );
+    }
+
+    #[test]
+    fn test_skips_links_in_tarballs() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (kind, path) in [
+            (tar::EntryType::Symlink, "rules/passwd.mdc"),
+            (tar::EntryType::Link, "rules/hard.mdc"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(kind);
+            header.set_size(0);
+            header.set_mode(0o777);
+            builder
+                .append_link(&mut header, path, "/etc/passwd")
+                .unwrap();
+        }
+        let bytes = builder.into_inner().unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let archive = temp.path().join("rules.tar");
+        fs::write(&archive, bytes).unwrap();
+        let unpacked = unpack_archive(&archive).unwrap();
+        assert!(fs::symlink_metadata(unpacked.path().join("rules/passwd.mdc")).is_err());
+        assert!(fs::symlink_metadata(unpacked.path().join("rules/hard.mdc")).is_err());
+    }
+}
@@ -0,0 +1,995 @@
+use log::info;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::changelog::{compute_changelog, read_manifest, render_changelog};
+use super::color::{eprint_colored, print_colored, Color};
+use super::common::{verify_targets_exist, write_target_with_parents};
+use super::manifest::write_manifest;
+use super::options::{ConvertOptions, OutputFormat, OverwritePolicy};
+use crate::error::{Result, RulerError};
+
+/// The result of converting one source file.
+#[derive(Debug, Clone)]
+pub struct FileOutcome {
+    pub source: PathBuf,
+    /// Path of the source relative to the source directory.
+    pub relative: PathBuf,
+    /// Files written on success, or the expected targets on failure.
+    pub targets: Vec<PathBuf>,
+    pub error: Option<String>,
+    /// Non-fatal problems found in the source, reported alongside the result.
+    pub warnings: Vec<String>,
+    /// The source was skipped because it is not valid UTF-8; `error` says so.
+    pub not_utf8: bool,
+    /// Targets that already had the converted contents and were left untouched.
+    pub unchanged: Vec<PathBuf>,
+    /// The target already existed and `--overwrite` kept it, so the source
+    /// was not converted.
+    pub kept: bool,
+    /// Source metadata fields that had a value but were left out of the
+    /// targets.
+    pub dropped: Vec<String>,
+}
+
+impl FileOutcome {
+    /// Records `error` as the reason the source failed.
+    pub fn set_error(&mut self, error: &RulerError) {
+        self.error = Some(error.to_string());
+        self.not_utf8 = matches!(error, RulerError::NotUtf8 { .. });
+    }
+
+    /// Whether the source failed or produced warnings, either of which
+    /// aborts a `--strict` run.
+    pub fn has_problems(&self) -> bool {
+        self.error.is_some() || !self.warnings.is_empty()
+    }
+}
+
+/// What a successful conversion of one source produced.
+#[derive(Debug, Default)]
+pub struct Converted {
+    /// Every file written for the source.
+    pub targets: Vec<PathBuf>,
+    pub warnings: Vec<String>,
+    /// The targets that already had the converted contents, so weren't rewritten.
+    pub unchanged: Vec<PathBuf>,
+    /// Source metadata fields with a value that no target carries.
+    pub dropped: Vec<String>,
+}
+
+impl Converted {
+    /// A conversion that produced `target`, as reported by `write_target`.
+    pub fn target(target: &Path, written: bool) -> Self {
+        let mut converted = Converted::default();
+        converted.push(target.to_path_buf(), written);
+        converted
+    }
+
+    /// Adds `target`, noting it as unchanged unless it was `written`.
+    pub fn push(&mut self, target: PathBuf, written: bool) {
+        if !written {
+            self.unchanged.push(target.clone());
+        }
+        self.targets.push(target);
+    }
+}
+
+impl From<Vec<PathBuf>> for Converted {
+    fn from(targets: Vec<PathBuf>) -> Self {
+        Converted {
+            targets,
+            warnings: Vec::new(),
+            unchanged: Vec::new(),
+            dropped: Vec::new(),
+        }
+    }
+}
+
+/// The sources of a [`run_batch_into`] run.
+#[derive(Debug, Default)]
+pub struct BatchSources {
+    /// The sources to convert.
+    pub convert: Vec<PathBuf>,
+    /// The other current sources, which aren't converted this run, such as
+    /// the rules `--watch` saw no change in.
+    pub unchanged: Vec<PathBuf>,
+}
+
+impl From<Vec<PathBuf>> for BatchSources {
+    fn from(convert: Vec<PathBuf>) -> Self {
+        BatchSources {
+            convert,
+            unchanged: Vec::new(),
+        }
+    }
+}
+
+/// Converts every file in `sources` in parallel and prints the summary. With
+/// `options.strict`, the first source that fails or warns stops any source not
+/// yet started from being converted.
+///
+/// `target_name` maps a source path relative to `from_dir` onto a target path
+/// relative to `to_dir`; with `options.flatten` only its file name is kept.
+/// `convert` receives the source path, its path relative
+/// to `from_dir` and the target path, writes the target and returns every file
+/// it produced along with any warnings about the source.
+///
+/// Every target file name ends with `target_suffix`; with `options.prune`,
+/// the targets the previous `--manifest` recorded for sources that are gone
+/// are deleted, see [`prune_orphans`]. It is `None` for runs that never prune, such as
+/// rewriting the sources in place.
+pub fn run_batch<N, C>(
+    from_dir: &Path,
+    to_dir: &Path,
+    target_suffix: Option<&str>,
+    sources: Vec<PathBuf>,
+    options: &ConvertOptions,
+    target_name: N,
+    convert: C,
+) -> Result<()>
+where
+    N: Fn(&Path) -> PathBuf + Sync,
+    C: Fn(&Path, &Path, &Path) -> Result<Converted> + Sync,
+{
+    run_batch_into(
+        from_dir,
+        &[to_dir.to_path_buf()],
+        target_suffix,
+        sources.into(),
+        options,
+        target_name,
+        // With a single target directory there is a single target
+        |source_file, relative_path, targets| convert(source_file, relative_path, &targets[0]),
+    )
+}
+
+/// Like [`run_batch`], writing every source into each of `to_dirs`. Each
+/// source is converted once: `convert` receives its target path in every
+/// directory whose target `--overwrite` doesn't keep, and the summary covers
+/// all of them.
+///
+/// With [`BatchSources::unchanged`] sources, a source whose target collides
+/// with one of theirs fails like any other collision, and nothing is pruned,
+/// as their targets aren't among the outcomes.
+pub fn run_batch_into<N, C>(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    target_suffix: Option<&str>,
+    sources: BatchSources,
+    options: &ConvertOptions,
+    target_name: N,
+    convert: C,
+) -> Result<()>
+where
+    N: Fn(&Path) -> PathBuf + Sync,
+    C: Fn(&Path, &Path, &[PathBuf]) -> Result<Converted> + Sync,
+{
+    // Walk order depends on the file system; sorting keeps the sources a
+    // `--strict -j 1` run skips reproducible
+    let BatchSources {
+        convert: mut sources,
+        unchanged,
+    } = sources;
+    sources.sort();
+    let text_output = options.format == OutputFormat::Text;
+    let target_name = |relative_path: &Path| {
+        let name = target_name(relative_path);
+        match name.file_name() {
+            Some(file_name) if options.flatten => PathBuf::from(file_name),
+            _ => name,
+        }
+    };
+
+    // Sources that would write the same target, such as `style.md` and
+    // `style.mdc`, or `a/style.mdc` and `b/style.mdc` with `--flatten`, are
+    // reported instead of silently overwriting each other
+    let mut sources_by_target: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for source_file in sources.iter().chain(&unchanged) {
+        if let Ok(relative_path) = source_file.strip_prefix(from_dir) {
+            sources_by_target
+                .entry(target_name(relative_path))
+                .or_default()
+                .push(source_file.clone());
+        }
+    }
+
+    let aborted = AtomicBool::new(false);
+    let convert_one = |source_file: &PathBuf| -> Result<FileOutcome> {
+        let relative_path = source_file
+            .strip_prefix(from_dir)
+            .map_err(|_| RulerError::RelativePath(source_file.clone()))?;
+        let name = target_name(relative_path);
+        let target_paths: Vec<PathBuf> = to_dirs.iter().map(|to_dir| to_dir.join(&name)).collect();
+        for target_path in &target_paths {
+            info!(
+                "converting {} -> {}",
+                source_file.display(),
+                target_path.display()
+            );
+        }
+        let outcome = |targets, error, warnings, unchanged| FileOutcome {
+            source: source_file.clone(),
+            relative: relative_path.to_path_buf(),
+            targets,
+            error,
+            warnings,
+            not_utf8: false,
+            unchanged,
+            kept: false,
+            dropped: Vec::new(),
+        };
+
+        // Create parent directories if they don't exist
+        for parent in target_paths
+            .iter()
+            .filter_map(|target_path| target_path.parent())
+            .filter(|_| !options.check)
+        {
+            if let Err(e) = fs::create_dir_all(parent) {
+                let message = format!("Error creating directory {}: {}", parent.display(), e);
+                if text_output {
+                    eprint_colored(options.color, Color::Red, &message);
+                }
+                return Ok(outcome(target_paths, Some(message), Vec::new(), Vec::new()));
+            }
+        }
+
+        let (kept, to_convert): (Vec<PathBuf>, Vec<PathBuf>) = target_paths
+            .iter()
+            .cloned()
+            .partition(|target_path| keeps_target(source_file, target_path, options));
+        let result = match sources_by_target.get(&name) {
+            Some(colliding) if colliding.len() > 1 => Err(RulerError::TargetCollision {
+                target: target_paths[0].clone(),
+                sources: colliding.clone(),
+            }),
+            _ => {
+                if text_output && !options.check && !options.quiet {
+                    for target_path in &kept {
+                        print_kept(source_file, target_path, options);
+                    }
+                }
+                if to_convert.is_empty() {
+                    let mut kept = outcome(kept, None, Vec::new(), Vec::new());
+                    kept.kept = true;
+                    return Ok(kept);
+                }
+                convert(source_file, relative_path, &to_convert)
+            }
+        };
+        match result {
+            Ok(Converted {
+                mut targets,
+                warnings,
+                mut unchanged,
+                dropped,
+            }) => {
+                if text_output {
+                    for warning in warnings.iter().filter(|_| !options.report_only_errors) {
+                        let line = format!("Warning: {}: {}", source_file.display(), warning);
+                        eprint_colored(options.color, Color::Yellow, &line);
+                    }
+                    // Like `cargo fmt --check`, only problems are reported
+                    for target in targets.iter().filter(|_| !options.check && !options.quiet) {
+                        print_target(source_file, target, unchanged.contains(target), options);
+                    }
+                }
+                // A target kept in one directory is left as it was
+                unchanged.extend(kept.iter().cloned());
+                targets.extend(kept);
+                let mut converted = outcome(targets, None, warnings, unchanged);
+                converted.dropped = dropped;
+                Ok(converted)
+            }
+            Err(e) => {
+                if text_output && options.check {
+                    eprint_colored(options.color, Color::Red, &e.to_string());
+                } else if text_output {
+                    let line = format!("Error converting {}: {}", source_file.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
+                let mut failed = outcome(target_paths, None, Vec::new(), Vec::new());
+                failed.set_error(&e);
+                Ok(failed)
+            }
+        }
+    };
+
+    let pool = build_pool(options)?;
+    let outcomes = pool.install(|| {
+        sources
+            .par_iter()
+            .map(|source_file| -> Result<Option<FileOutcome>> {
+                if aborted.load(Ordering::Relaxed) {
+                    return Ok(None);
+                }
+                let outcome = convert_one(source_file)?;
+                if options.strict && outcome.has_problems() {
+                    aborted.store(true, Ordering::Relaxed);
+                }
+                Ok(Some(outcome))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let outcomes: Vec<FileOutcome> = outcomes.into_iter().flatten().collect();
+    let skipped = sources.len() - outcomes.len();
+
+    let prunes = options.prune && skipped == 0 && unchanged.is_empty();
+    if let Some(suffix) = target_suffix.filter(|_| prunes) {
+        for to_dir in to_dirs {
+            prune_orphans(to_dir, suffix, &outcomes, options)?;
+        }
+    }
+    finish_batch(&outcomes, skipped, options)
+}
+
+/// `--prune`: deletes the targets under `to_dir` that the previous run
+/// recorded in the `--manifest` file but that no source produced this time,
+/// printing each one. Such a target is only an orphan when its source is gone
+/// or was converted into other targets this run: a source that discovery left
+/// out, e.g. with `--exclude` or `.gitignore`, or that failed still owns its
+/// target, and files ruler didn't write are never touched. Orphans must also
+/// be named like targets, ending with `suffix`. With `options.check` they are
+/// only listed. Callers skip this after a `--strict` run stopped early, whose
+/// skipped sources still own their targets.
+pub fn prune_orphans(
+    to_dir: &Path,
+    suffix: &str,
+    outcomes: &[FileOutcome],
+    options: &ConvertOptions,
+) -> Result<Vec<PathBuf>> {
+    // Without a previous manifest nothing is known to be generated
+    let Some(manifest) = options.manifest.as_deref().filter(|path| path.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let previous = read_manifest(manifest)?;
+    let targets: HashSet<&Path> = outcomes
+        .iter()
+        .flat_map(|o| &o.targets)
+        .map(PathBuf::as_path)
+        .collect();
+    let converted: HashSet<&Path> = outcomes
+        .iter()
+        .filter(|o| o.error.is_none())
+        .map(|o| o.source.as_path())
+        .collect();
+
+    let mut orphans: Vec<PathBuf> = previous
+        .conversions
+        .iter()
+        .filter(|c| c.status == ConversionStatus::Success)
+        .filter(|c| {
+            let source = Path::new(&c.source);
+            !source.exists() || converted.contains(source)
+        })
+        .map(|c| PathBuf::from(&c.target))
+        .filter(|target| {
+            target.starts_with(to_dir)
+                && target.is_file()
+                && !targets.contains(target.as_path())
+                && target
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(suffix))
+        })
+        .collect();
+    orphans.sort();
+    orphans.dedup();
+
+    for orphan in &orphans {
+        if !options.check {
+            fs::remove_file(orphan).map_err(|source| RulerError::Remove {
+                path: orphan.clone(),
+                source,
+            })?;
+        }
+        if options.format == OutputFormat::Text && !options.quiet {
+            let verb = if options.check {
+                "Would prune"
+            } else {
+                "Pruned"
+            };
+            let line = format!("{}: {}", verb, orphan.display());
+            print_colored(options.color, Color::Green, &line);
+        }
+    }
+    Ok(orphans)
+}
+
+/// Whether `--overwrite` keeps the existing `target` of `source` instead of
+/// converting it again. A target that can't be compared by modification time
+/// is replaced.
+pub fn keeps_target(source: &Path, target: &Path, options: &ConvertOptions) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match options.overwrite {
+        OverwritePolicy::Always => false,
+        OverwritePolicy::Never => target.exists(),
+        OverwritePolicy::IfNewer => match (modified(source), modified(target)) {
+            (Some(source), Some(target)) => source <= target,
+            _ => false,
+        },
+    }
+}
+
+/// Prints the progress line for one target written from `source`, or left
+/// as it was when it was already `unchanged`.
+pub fn print_target(source: &Path, target: &Path, unchanged: bool, options: &ConvertOptions) {
+    let verb = if unchanged { "Unchanged" } else { "Converted" };
+    let line = format!("{}: {} -> {}", verb, source.display(), target.display());
+    print_colored(options.color, Color::Green, &line);
+}
+
+/// Prints the progress line for a `target` of `source` that `--overwrite` kept.
+pub fn print_kept(source: &Path, target: &Path, options: &ConvertOptions) {
+    let line = format!("Kept: {} -> {}", source.display(), target.display());
+    print_colored(options.color, Color::Green, &line);
+}
+
+/// Writes `rules`, `(path relative to to_dir, contents)` pairs that were all
+/// read from the single file `source`, then prints the summary.
+pub fn write_split_targets(
+    source: &Path,
+    to_dir: &Path,
+    rules: Vec<(PathBuf, String)>,
+    options: &ConvertOptions,
+) -> Result<()> {
+    let text_output = options.format == OutputFormat::Text;
+    let total = rules.len();
+    let mut outcomes = Vec::new();
+    for (relative, rule) in rules {
+        let target = to_dir.join(&relative);
+        let mut outcome = FileOutcome {
+            source: source.to_path_buf(),
+            relative,
+            targets: vec![target.clone()],
+            error: None,
+            warnings: Vec::new(),
+            not_utf8: false,
+            unchanged: Vec::new(),
+            kept: false,
+            dropped: Vec::new(),
+        };
+        match write_target_with_parents(&target, &rule, options) {
+            Ok(written) => {
+                if !written {
+                    outcome.unchanged.push(target.clone());
+                }
+                if text_output && !options.check && !options.quiet {
+                    print_target(source, &target, !written, options);
+                }
+            }
+            Err(e) => {
+                if text_output && options.check {
+                    eprint_colored(options.color, Color::Red, &e.to_string());
+                } else if text_output {
+                    let line = format!("Error writing {}: {}", target.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
+                outcome.set_error(&e);
+            }
+        }
+        let abort = options.strict && outcome.has_problems();
+        outcomes.push(outcome);
+        if abort {
+            break;
+        }
+    }
+
+    finish_batch(&outcomes, total - outcomes.len(), options)
+}
+
+/// Prints the summary for a finished run and applies the checks that look at
+/// every outcome at once (`--check`, `--strict`, `--verify-complete`,
+/// `--fail-on-warning`), writing the `--manifest` if one was requested.
+/// `skipped` counts the sources a `--strict` run never got to. A run in which
+/// any source failed, including a non-UTF-8 source that was skipped, returns
+/// [`RulerError::Failed`] once everything else is done.
+pub fn finish_batch(
+    outcomes: &[FileOutcome],
+    skipped: usize,
+    options: &ConvertOptions,
+) -> Result<()> {
+    let error_count = outcomes.iter().filter(|o| o.error.is_some()).count();
+    let success_count = outcomes.len() - error_count;
+    let warning_count: usize = outcomes.iter().map(|o| o.warnings.len()).sum();
+    let not_utf8_count = outcomes.iter().filter(|o| o.not_utf8).count();
+    let unchanged_count: usize = outcomes.iter().map(|o| o.unchanged.len()).sum();
+    let kept_count = outcomes.iter().filter(|o| o.kept).count();
+    let aborted = options.strict && outcomes.iter().any(FileOutcome::has_problems);
+    match options.format {
+        OutputFormat::Text if options.diff => {
+            let changed = outcomes
+                .iter()
+                .filter(|o| o.error.is_none())
+                .map(|o| o.targets.len() - o.unchanged.len())
+                .sum::<usize>();
+            println!("{} target(s) would change.", changed);
+        }
+        OutputFormat::Text if options.check => {
+            if error_count == 0 {
+                let line = format!("All {} target(s) are up to date.", success_count);
+                print_colored(options.color, Color::Green, &line);
+            }
+        }
+        OutputFormat::Text if aborted => {
+            let line = format!(
+                "Conversion aborted with --strict after {} successes, {} errors and {} warnings; {} file(s) skipped.",
+                success_count, error_count, warning_count, skipped
+            );
+            print_colored(options.color, Color::Red, &line);
+        }
+        OutputFormat::Text => {
+            if not_utf8_count > 0 {
+                let line = format!(
+                    "Conversion completed with {} successes, {} errors and {} non-UTF-8 file(s) skipped.",
+                    success_count,
+                    error_count - not_utf8_count,
+                    not_utf8_count
+                );
+                print_colored(options.color, Color::Red, &line);
+            } else if error_count > 0 {
+                let line = format!(
+                    "Conversion completed with {} successes and {} errors.",
+                    success_count, error_count
+                );
+                print_colored(options.color, Color::Red, &line);
+            } else {
+                print_colored(
+                    options.color,
+                    Color::Green,
+                    "Conversion completed successfully!",
+                );
+            }
+            if options.report_only_errors {
+                print!("{}", render_failures(outcomes));
+            } else if unchanged_count > 0 {
+                println!(
+                    "{} target(s) unchanged and left as they were.",
+                    unchanged_count
+                );
+            }
+            if kept_count > 0 && !options.report_only_errors {
+                println!("{} existing target(s) kept by --overwrite.", kept_count);
+            }
+            if !options.report_only_errors {
+                print!("{}", render_dropped_fields(outcomes));
+            }
+        }
+        OutputFormat::Tap => print!("{}", render_tap(outcomes)),
+        OutputFormat::Json => println!("{}", render_json(outcomes)?),
+    }
+
+    if let Some(manifest) = &options.changelog_against {
+        let previous = read_manifest(manifest)?;
+        let changelog = render_changelog(&compute_changelog(&previous, &json_report(outcomes)));
+        // Keep stdout a single JSON document
+        if options.format == OutputFormat::Json {
+            eprint!("\n{}", changelog);
+        } else {
+            print!("\n{}", changelog);
+        }
+    }
+
+    // Like the targets themselves, the manifest is not written in check mode
+    if let Some(manifest) = options.manifest.as_ref().filter(|_| !options.check) {
+        write_manifest(manifest, outcomes, options)?;
+    }
+
+    if options.check && !options.diff && error_count > 0 {
+        return Err(RulerError::CheckFailed(error_count));
+    }
+
+    if aborted {
+        return Err(RulerError::Strict {
+            errors: error_count,
+            warnings: warning_count,
+        });
+    }
+
+    if options.verify_complete {
+        let expected_targets: Vec<(PathBuf, PathBuf)> = outcomes
+            .iter()
+            .flat_map(|o| o.targets.iter().map(|t| (o.source.clone(), t.clone())))
+            .collect();
+        verify_targets_exist(&expected_targets)?;
+    }
+
+    if error_count > 0 {
+        return Err(RulerError::Failed(error_count));
+    }
+
+    if options.fail_on_warning && warning_count > 0 {
+        return Err(RulerError::Warnings(warning_count));
+    }
+    Ok(())
+}
+
+/// Lists every source that failed with its error, or returns an empty string
+/// when none did.
+pub fn render_failures(outcomes: &[FileOutcome]) -> String {
+    let mut failures = String::new();
+    for outcome in outcomes {
+        if let Some(error) = &outcome.error {
+            failures.push_str(&format!("  {}: {}\n", outcome.source.display(), error));
+        }
+    }
+    if failures.is_empty() {
+        failures
+    } else {
+        format!("Failed:\n{}", failures)
+    }
+}
+
+/// Lists, for each metadata field some source lost in conversion, the
+/// sources that had it, or returns an empty string when nothing was dropped.
+pub fn render_dropped_fields(outcomes: &[FileOutcome]) -> String {
+    let mut by_field: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for outcome in outcomes.iter().filter(|o| o.error.is_none()) {
+        for field in &outcome.dropped {
+            by_field
+                .entry(field)
+                .or_default()
+                .push(outcome.relative.display().to_string());
+        }
+    }
+    if by_field.is_empty() {
+        return String::new();
+    }
+
+    let mut summary = String::from("Fields not carried over:\n");
+    for (field, sources) in by_field {
+        summary.push_str(&format!("  {}: {}\n", field, sources.join(", ")));
+    }
+    summary
+}
+
+/// Renders outcomes as a TAP (Test Anything Protocol) stream: a plan line,
+/// one `ok`/`not ok` line per source file and a summary comment.
+pub fn render_tap(outcomes: &[FileOutcome]) -> String {
+    let mut tap = format!("1..{}\n", outcomes.len());
+    let mut failed = 0;
+
+    for (i, outcome) in outcomes.iter().enumerate() {
+        let name = outcome.relative.display();
+        match &outcome.error {
+            None => {
+                tap.push_str(&format!("ok {} - {}\n", i + 1, name));
+                for warning in &outcome.warnings {
+                    tap.push_str(&format!("# warning: {}\n", warning));
+                }
+            }
+            Some(error) => {
+                failed += 1;
+                let reason = error.lines().collect::<Vec<_>>().join(" ");
+                tap.push_str(&format!("not ok {} - {} # {}\n", i + 1, name, reason));
+            }
+        }
+    }
+
+    tap.push_str(&format!(
+        "# {} passed, {} failed\n",
+        outcomes.len() - failed,
+        failed
+    ));
+    tap
+}
+
+/// Report emitted by `--format json`. Field names and status values are part
+/// of the tool's stable output and should not change. A saved report doubles
+/// as the manifest read by `--changelog-against`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonReport {
+    /// One entry per target file (a source may produce several).
+    pub conversions: Vec<JsonConversion>,
+    /// Number of source files converted successfully.
+    pub success_count: usize,
+    /// Number of source files that failed to convert.
+    pub error_count: usize,
+    /// Number of targets that already had the converted contents and were
+    /// not rewritten.
+    #[serde(default)]
+    pub unchanged_count: usize,
+    /// Number of existing targets `--overwrite` kept instead of converting.
+    #[serde(default)]
+    pub kept_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonConversion {
+    pub source: String,
+    pub target: String,
+    pub status: ConversionStatus,
+    /// Error message when `status` is `error`, otherwise `null`.
+    pub error: Option<String>,
+    /// Warnings about the source; omitted when there are none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Digest of the target's contents after a successful conversion, used to
+    /// tell updated targets from unchanged ones; omitted when unavailable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// The target already had the converted contents and was not rewritten;
+    /// omitted when it was written.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unchanged: bool,
+    /// `--overwrite` kept the existing target; omitted when it didn't.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kept: bool,
+    /// Source metadata fields with a value the target left out; omitted
+    /// when there are none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionStatus {
+    Success,
+    Error,
+}
+
+/// Renders outcomes as the pretty-printed `--format json` report.
+pub fn render_json(outcomes: &[FileOutcome]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&json_report(outcomes))?)
+}
+
+/// Builds the `--format json` report for `outcomes`, reading each written
+/// target to record its digest.
+pub fn json_report(outcomes: &[FileOutcome]) -> JsonReport {
+    let mut report = JsonReport {
+        conversions: Vec::new(),
+        success_count: 0,
+        error_count: 0,
+        unchanged_count: 0,
+        kept_count: 0,
+    };
+
+    for outcome in outcomes {
+        let status = if outcome.error.is_some() {
+            report.error_count += 1;
+            ConversionStatus::Error
+        } else {
+            report.success_count += 1;
+            ConversionStatus::Success
+        };
+        report.kept_count += usize::from(outcome.kept);
+        for target in &outcome.targets {
+            report.conversions.push(JsonConversion {
+                source: outcome.source.display().to_string(),
+                target: target.display().to_string(),
+                status,
+                error: outcome.error.clone(),
+                warnings: outcome.warnings.clone(),
+                digest: outcome
+                    .error
+                    .is_none()
+                    .then(|| content_digest(target))
+                    .flatten(),
+                unchanged: outcome.unchanged.contains(target),
+                kept: outcome.kept,
+                dropped: outcome.dropped.clone(),
+            });
+            report.unchanged_count += usize::from(outcome.unchanged.contains(target));
+        }
+    }
+
+    report
+}
+
+// 64-bit FNV-1a of the file's bytes: stable across runs and platforms, which
+// is all a manifest comparison needs.
+fn content_digest(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Some(format!("{:016x}", hash))
+}
+
+// Sizes the pool from `--jobs`, falling back to rayon's default of one thread per core.
+fn build_pool(options: &ConvertOptions) -> Result<rayon::ThreadPool> {
+    let mut builder = rayon::ThreadPoolBuilder::new();
+    if let Some(jobs) = options.jobs {
+        builder = builder.num_threads(jobs);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        render_dropped_fields, render_json, render_tap, run_batch, Converted, FileOutcome,
+    };
+    use crate::parser::changelog::read_manifest;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_run_batch_converts_every_source_in_parallel() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("from");
+        let to_dir = temp.path().join("to");
+        fs::create_dir_all(from_dir.join("nested")).unwrap();
+        let mut sources = Vec::new();
+        for i in 0..20 {
+            let source = from_dir.join("nested").join(format!("rule{}.txt", i));
+            fs::write(&source, format!("rule {}", i)).unwrap();
+            sources.push(source);
+        }
+
+        let options = ConvertOptions {
+            jobs: Some(4),
+            verify_complete: true,
+            ..Default::default()
+        };
+        run_batch(
+            &from_dir,
+            &to_dir,
+            None,
+            sources,
+            &options,
+            |relative: &Path| relative.with_extension("out"),
+            |source: &Path, _relative: &Path, target: &Path| -> crate::error::Result<Converted> {
+                fs::write(target, fs::read_to_string(source).unwrap().to_uppercase()).unwrap();
+                Ok(vec![target.to_path_buf()].into())
+            },
+        )
+        .unwrap();
+
+        for i in 0..20 {
+            let target = to_dir.join("nested").join(format!("rule{}.out", i));
+            assert_eq!(fs::read_to_string(target).unwrap(), format!("RULE {}", i));
+        }
+    }
+
+    #[test]
+    fn test_overwrite_policies() {
+        use crate::parser::options::OverwritePolicy;
+        use std::time::{Duration, SystemTime};
+
+        let set_modified = |path: &Path, secs: u64| {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+        let converted = |policy| {
+            let temp = tempfile::tempdir().unwrap();
+            let from_dir = temp.path().join("from");
+            let to_dir = temp.path().join("to");
+            fs::create_dir_all(&from_dir).unwrap();
+            fs::create_dir_all(&to_dir).unwrap();
+            // `stale` was edited after its target was written, `fresh` before
+            for (name, source_time, target_time) in [("stale", 2000, 1000), ("fresh", 1000, 2000)] {
+                fs::write(from_dir.join(name), "new").unwrap();
+                fs::write(to_dir.join(name), "old").unwrap();
+                set_modified(&from_dir.join(name), source_time);
+                set_modified(&to_dir.join(name), target_time);
+            }
+            fs::write(from_dir.join("missing"), "new").unwrap();
+
+            let manifest = temp.path().join("manifest.json");
+            let options = ConvertOptions {
+                overwrite: policy,
+                manifest: Some(manifest.clone()),
+                ..Default::default()
+            };
+            let sources = ["stale", "fresh", "missing"].map(|name| from_dir.join(name));
+            run_batch(
+                &from_dir,
+                &to_dir,
+                None,
+                sources.to_vec(),
+                &options,
+                |relative: &Path| relative.to_path_buf(),
+                |source: &Path,
+                 _relative: &Path,
+                 target: &Path|
+                 -> crate::error::Result<Converted> {
+                    fs::write(target, fs::read_to_string(source).unwrap()).unwrap();
+                    Ok(vec![target.to_path_buf()].into())
+                },
+            )
+            .unwrap();
+            let contents = ["stale", "fresh", "missing"]
+                .map(|name| fs::read_to_string(to_dir.join(name)).unwrap());
+            (contents, read_manifest(&manifest).unwrap().kept_count)
+        };
+
+        assert_eq!(
+            converted(OverwritePolicy::Always),
+            (["new", "new", "new"].map(String::from), 0)
+        );
+        assert_eq!(
+            converted(OverwritePolicy::Never),
+            (["old", "old", "new"].map(String::from), 2)
+        );
+        assert_eq!(
+            converted(OverwritePolicy::IfNewer),
+            (["new", "old", "new"].map(String::from), 1)
+        );
+    }
+
+    fn sample_outcomes() -> Vec<FileOutcome> {
+        vec![
+            FileOutcome {
+                source: PathBuf::from("rules/style.mdc"),
+                relative: PathBuf::from("style.mdc"),
+                targets: vec![PathBuf::from("out/style.instructions.md")],
+                error: None,
+                warnings: Vec::new(),
+                not_utf8: false,
+                unchanged: Vec::new(),
+                kept: false,
+                dropped: Vec::new(),
+            },
+            FileOutcome {
+                source: PathBuf::from("rules/broken.mdc"),
+                relative: PathBuf::from("broken.mdc"),
+                targets: vec![PathBuf::from("out/broken.instructions.md")],
+                error: Some("parse error".to_string()),
+                warnings: Vec::new(),
+                not_utf8: false,
+                unchanged: Vec::new(),
+                kept: false,
+                dropped: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_tap() {
+        let outcomes = sample_outcomes();
+        assert_eq!(
+            render_tap(&outcomes),
+            "1..2\nok 1 - style.mdc\nnot ok 2 - broken.mdc # parse error\n# 1 passed, 1 failed\n"
+        );
+    }
+
+    #[test]
+    fn test_render_dropped_fields_groups_sources_by_field() {
+        let mut outcomes = sample_outcomes();
+        assert_eq!(render_dropped_fields(&outcomes), "");
+
+        outcomes[0].dropped = vec!["tags".to_string(), "version".to_string()];
+        outcomes.push(FileOutcome {
+            relative: PathBuf::from("lint.mdc"),
+            dropped: vec!["tags".to_string()],
+            ..outcomes[0].clone()
+        });
+        assert_eq!(
+            render_dropped_fields(&outcomes),
+            "Fields not carried over:\n  tags: style.mdc, lint.mdc\n  version: style.mdc\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&sample_outcomes()).unwrap()).unwrap();
+
+        assert_eq!(json["success_count"], 1);
+        assert_eq!(json["error_count"], 1);
+        assert_eq!(
+            json["conversions"][0],
+            serde_json::json!({
+                "source": "rules/style.mdc",
+                "target": "out/style.instructions.md",
+                "status": "success",
+                "error": null
+            })
+        );
+        assert_eq!(json["conversions"][1]["status"], "error");
+        assert_eq!(json["conversions"][1]["error"], "parse error");
+    }
+}
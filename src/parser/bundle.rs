@@ -0,0 +1,207 @@
+//! Rule bundles: several Cursor rules kept in one file, each a `---` delimited
+//! frontmatter block followed by its body. `bundle2c` splits a bundle into
+//! `.mdc` rules and `bundle2g` into GitHub instructions, one file per rule
+//! named after its `name` field.
+//!
+//! ```markdown
+//! ---
+//! name: Style
+//! globs: "*.ts"
+//! ---
+//! Use two spaces.
+//! ---
+//! name: Testing
+//! ---
+//! Write a test for every fix.
+//! ```
+//!
+//! A `---` line only starts the next rule when the block it opens has a
+//! `name:` key, so bodies can still use `---` as a horizontal rule.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::batch::write_split_targets;
+use super::c2g::convert_cursor_content_with_options;
+use super::common::{
+    file_slug, parse_cursor_metadata, parse_frontmatter_with_field_info, read_source_file,
+};
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+/// Splits a bundle into `(name, contents)` pairs, one Cursor rule per
+/// document, in bundle order. Fails if a document has no `name` or two
+/// documents share one.
+pub fn split_bundle_content(content: &str) -> Result<Vec<(String, String)>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let first = lines.iter().position(|line| !line.trim().is_empty());
+    let Some(first) = first.filter(|&i| lines[i].trim() == "---") else {
+        return Err(RulerError::Bundle(
+            "expected a `---` line before the first rule".to_string(),
+        ));
+    };
+
+    // Delimiters outside fenced code blocks
+    let mut delimiters = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate().skip(first) {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && line.trim() == "---" {
+            delimiters.push(i);
+        }
+    }
+    let opens_rule = |d: usize| {
+        delimiters.get(d + 1).is_some_and(|&close| {
+            lines[delimiters[d] + 1..close]
+                .iter()
+                .any(|line| line.starts_with("name:"))
+        })
+    };
+
+    // Each rule runs from its opening delimiter to the next delimiter that
+    // opens a named block
+    let mut starts = Vec::new();
+    let mut d = 0;
+    while d + 1 < delimiters.len() {
+        starts.push(delimiters[d]);
+        d += 2;
+        while d < delimiters.len() && !opens_rule(d) {
+            d += 1;
+        }
+    }
+
+    let mut rules = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (index, &start) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).copied().unwrap_or(lines.len());
+        let document = lines[start..end].join("\n");
+        let (frontmatter, _, field_info) = parse_frontmatter_with_field_info(&document)?;
+        let name = match frontmatter {
+            Some(fm) => parse_cursor_metadata(&fm, field_info.format)?.name,
+            None => None,
+        };
+        let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let Some(name) = name else {
+            return Err(RulerError::Bundle(format!(
+                "rule {} has no `name`",
+                index + 1
+            )));
+        };
+        if let Some(previous) = seen.insert(file_slug(&name, "rule"), index + 1) {
+            return Err(RulerError::Bundle(format!(
+                "rules {} and {} are both named `{}`",
+                previous,
+                index + 1,
+                name
+            )));
+        }
+        rules.push((name, format!("{}\n", document.trim())));
+    }
+    Ok(rules)
+}
+
+/// Splits the bundle `source` into `.mdc` Cursor rules under `to_dir`.
+pub fn split_bundle_to_cursor(
+    source: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    split_bundle(source, to_dir, options, |name, rule| {
+        Ok((format!("{}.mdc", file_slug(name, "rule")), rule))
+    })
+}
+
+/// Splits the bundle `source` into GitHub Copilot instructions under `to_dir`.
+pub fn split_bundle_to_github(
+    source: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    options.validate_target_extension()?;
+    split_bundle(source, to_dir, options, |name, rule| {
+        let slug = file_slug(name, "rule");
+        let rule_path = PathBuf::from(format!("{}.mdc", slug));
+        Ok((
+            format!("{}{}", slug, options.instructions_suffix()),
+            convert_cursor_content_with_options(&rule, options, Some(&rule_path))?,
+        ))
+    })
+}
+
+fn split_bundle<R>(source: &Path, to_dir: &Path, options: &ConvertOptions, render: R) -> Result<()>
+where
+    R: Fn(&str, String) -> Result<(String, String)>,
+{
+    if options.shows_progress() {
+        println!("Splitting rule bundle...");
+        println!("From: {}", source.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    let rule_error = |e| RulerError::Rule {
+        path: source.to_path_buf(),
+        source: Box::new(e),
+    };
+    let content = read_source_file(source, options)?;
+    let rules = split_bundle_content(&content)
+        .and_then(|rules| {
+            rules
+                .into_iter()
+                .map(|(name, rule)| {
+                    render(&name, rule).map(|(file, output)| (PathBuf::from(file), output))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .map_err(rule_error)?;
+
+    write_split_targets(source, to_dir, rules, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_bundle_content, split_bundle_to_cursor};
+    use crate::parser::options::{ConvertOptions, OutputFormat};
+    use std::fs;
+
+    const BUNDLE: &str = "---\nname: Code Style\nglobs: \"*.ts\"\n---\nUse two spaces.\n\n---\n\nNot a new rule.\n---\nname: testing\ndescription: Tests\n---\nWrite tests.\n---\nname: Docs\nalwaysApply: true\n---\n```yaml\n---\nname: example\n---\n```\n";
+
+    #[test]
+    fn test_three_rule_bundle_becomes_three_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("rules.md");
+        let to_dir = temp.path().join("rules");
+        fs::write(&source, BUNDLE).unwrap();
+        let options = ConvertOptions {
+            format: OutputFormat::Json,
+            ..Default::default()
+        };
+        split_bundle_to_cursor(&source, &to_dir, &options).unwrap();
+
+        assert_eq!(fs::read_dir(&to_dir).unwrap().count(), 3);
+        assert_eq!(
+            fs::read_to_string(to_dir.join("code-style.mdc")).unwrap(),
+            "---\nname: Code Style\nglobs: \"*.ts\"\n---\nUse two spaces.\n\n---\n\nNot a new rule.\n"
+        );
+        assert!(to_dir.join("testing.mdc").exists());
+        assert!(fs::read_to_string(to_dir.join("docs.mdc"))
+            .unwrap()
+            .ends_with("```yaml\n---\nname: example\n---\n```\n"));
+    }
+
+    #[test]
+    fn test_bundle_names_must_be_present_and_unique() {
+        let duplicate = "---\nname: Style\n---\nA.\n---\nname: style\n---\nB.\n";
+        let err = split_bundle_content(duplicate).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid rule bundle: rules 1 and 2 are both named `style`"
+        );
+
+        let unnamed = "---\ndescription: No name\n---\nA.\n";
+        let err = split_bundle_content(unnamed).unwrap_err();
+        assert!(err.to_string().contains("rule 1 has no `name`"));
+    }
+}
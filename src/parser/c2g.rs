@@ -1,171 +1,1659 @@
-use anyhow::{Context, Result};
+use log::debug;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use super::batch::{
+    finish_batch, print_target, prune_orphans, run_batch_into, BatchSources, Converted, FileOutcome,
+};
+use super::color::{eprint_colored, Color};
 use super::common::{
-    find_cursor_files, parse_frontmatter_with_field_info, preprocess_frontmatter,
-    CursorMetadata, GithubMetadata
+    check_source_dir, file_slug, find_cursor_files, first_heading, is_cursor_file, literal_block,
+    normalize_globs, normalize_tags, parse_cursor_metadata, parse_frontmatter_with_field_info,
+    quote_string, reorder_fields, restore_comments, serialize_frontmatter, split_globs,
+    target_name_c2g, with_source_body, write_target, CursorMetadata, GithubMetadata, LineEnding,
+    GITHUB_FIELDS,
 };
+use super::defaults::{expand_changed_defaults, read_cursor_rule};
+use super::language::glob_languages;
+use super::lint::lint_cursor_rule;
+use super::merge::{merge_sections, RuleSection};
+use super::options::{ConvertOptions, FrontmatterFormat, OutputFormat};
+use super::watch::watch_sources;
+use crate::error::{Result, RulerError};
+
+pub fn convert_cursor_to_github(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    convert_cursor_to_github_targets(from_dir, &[to_dir.to_path_buf()], options)
+}
+
+/// Like [`convert_cursor_to_github`], writing every converted file into each
+/// of `to_dirs`. The source tree is walked and each rule converted once, and a
+/// single summary covers every target directory.
+pub fn convert_cursor_to_github_targets(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    options: &ConvertOptions,
+) -> Result<()> {
+    options.validate_target_extension()?;
+    options.validate_always_apply_glob()?;
+    check_source_dir(from_dir)?;
+    if options.shows_progress() {
+        println!("Converting Cursor rules to GitHub Copilot instructions...");
+        println!("From: {}", from_dir.display());
+    }
 
-pub fn convert_cursor_to_github(from_dir: &Path, to_dir: &Path) -> Result<()> {
-    println!("Converting Cursor rules to GitHub Copilot instructions...");
-    println!("From: {}", from_dir.display());
-    println!("To: {}", to_dir.display());
+    for to_dir in to_dirs {
+        if options.shows_progress() {
+            println!("To: {}", to_dir.display());
+        }
 
-    // Create target directory if it doesn't exist
-    fs::create_dir_all(to_dir)
-        .with_context(|| format!("Failed to create directory: {}", to_dir.display()))?;
+        // Create target directory if it doesn't exist; check mode writes nothing
+        if !options.check {
+            fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+                path: to_dir.to_path_buf(),
+                source,
+            })?;
+        }
+    }
 
     // Find all .mdc and .md files in the source directory
-    let source_files = find_cursor_files(from_dir)?;
+    let source_files = find_cursor_files(from_dir, options)?;
 
-    if source_files.is_empty() {
+    if source_files.is_empty() && options.shows_progress() {
         println!("No .mdc or .md files found in {}", from_dir.display());
-        return Ok(());
     }
 
-    let mut success_count = 0;
-    let mut error_count = 0;
+    convert_into(from_dir, to_dirs, source_files.into(), options)
+}
+
+/// Runs [`convert_cursor_to_github_targets`], then keeps watching `from_dir`
+/// and re-converts each rule that changes into every one of `to_dirs`, along
+/// with the rules beside a `_defaults.mdc` that changes. With
+/// `--by-language` the merged files depend on every rule, so any change
+/// converts the whole source tree again.
+pub fn watch_cursor_to_github(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    options: &ConvertOptions,
+) -> Result<()> {
+    match convert_cursor_to_github_targets(from_dir, to_dirs, options) {
+        // Rules that failed have been reported and can be fixed while watching
+        Err(RulerError::Failed(_)) => {}
+        result => result?,
+    }
+    watch_sources(from_dir, to_dirs, options, is_cursor_file, |changed| {
+        // Discovered like a full run, so ignored files stay unconverted and
+        // targets are checked for collisions with every rule
+        let rules = find_cursor_files(from_dir, options)?;
+        if options.by_language {
+            return convert_into(from_dir, to_dirs, rules.into(), options);
+        }
+        // Saving a `_defaults.mdc` refreshes the rules inheriting from it
+        let sources: Vec<PathBuf> = expand_changed_defaults(changed, &rules)
+            .into_iter()
+            .filter(|source| rules.contains(source))
+            .collect();
+        if sources.is_empty() {
+            return Ok(());
+        }
+        let unchanged = rules
+            .into_iter()
+            .filter(|rule| !sources.contains(rule))
+            .collect();
+        let sources = BatchSources {
+            convert: sources,
+            unchanged,
+        };
+        convert_into(from_dir, to_dirs, sources, options)
+    })
+}
+
+// Converts `sources` into every one of `to_dirs`.
+fn convert_into(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    sources: BatchSources,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.by_language {
+        return convert_by_language(from_dir, to_dirs, sources.convert, options);
+    }
+
+    run_batch_into(
+        from_dir,
+        to_dirs,
+        Some(options.instructions_suffix()),
+        sources,
+        options,
+        |relative_path| target_name_c2g(relative_path, options),
+        |source_file, relative_path, targets| {
+            let content = read_cursor_rule(source_file, options)?;
+            let warnings = lint_cursor_rule(&content, options)?;
+            let mut converted = if options.one_glob_per_file {
+                convert_mdc_to_md_per_glob(&content, relative_path, targets, options)?
+            } else {
+                convert_mdc_to_md(&content, relative_path, targets, options)?
+            };
+            converted.warnings = warnings;
+            converted.dropped = dropped_fields(&content, options)?;
+            Ok(converted)
+        },
+    )
+}
+
+// The frontmatter carries every Cursor field, but `--no-frontmatter` keeps
+// only the name and description, as the body's opening heading and
+// paragraph, and a `--template` without `{{frontmatter}}` keeps none.
+fn dropped_fields(content: &str, options: &ConvertOptions) -> Result<Vec<String>> {
+    let template_drops_frontmatter = options
+        .template
+        .as_deref()
+        .is_some_and(|template| !template.contains("{{frontmatter}}"));
+    if !options.no_frontmatter && !template_drops_frontmatter {
+        return Ok(Vec::new());
+    }
+    let (frontmatter, _, field_info) = parse_frontmatter_with_field_info(content)?;
+    let Some(fm) = frontmatter else {
+        return Ok(Vec::new());
+    };
+    let meta = parse_cursor_metadata(&fm, field_info.format)?;
+    let in_body = |field| options.no_frontmatter && matches!(field, "name" | "description");
+    Ok(present_fields(&meta)
+        .into_iter()
+        .filter(|&field| !in_body(field))
+        .map(str::to_string)
+        .collect())
+}
+
+// The Cursor fields `meta` has a value for, in frontmatter order.
+fn present_fields(meta: &CursorMetadata) -> Vec<&'static str> {
+    let fields = [
+        ("name", meta.name.is_some()),
+        (
+            "description",
+            meta.description
+                .as_deref()
+                .is_some_and(|d| !d.trim().is_empty()),
+        ),
+        ("globs", meta.globs.as_ref().is_some_and(|g| !g.is_empty())),
+        ("alwaysApply", meta.always_apply.is_some()),
+        ("authors", meta.authors.is_some()),
+        ("tags", meta.tags.is_some()),
+        ("version", meta.version.is_some()),
+        ("priority", meta.priority.is_some()),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(field, present)| present.then_some(field))
+        .collect()
+}
+
+fn convert_mdc_to_md(
+    content: &str,
+    relative_path: &Path,
+    targets: &[PathBuf],
+    options: &ConvertOptions,
+) -> Result<Converted> {
+    let output_content =
+        convert_cursor_content_with_options(content, options, Some(relative_path))?;
+    let output_content = with_source_body(content, output_content, options);
+
+    let mut converted = Converted::default();
+    for target in targets {
+        let written = write_target(target, &output_content, options)?;
+        converted.push(target.clone(), written);
+    }
+    Ok(converted)
+}
+
+// Writes one instruction file per glob, named `<stem>.<glob-slug>.instructions.md`,
+// beside each of `targets`. Rules with fewer than two globs are written to
+// `targets` unchanged.
+fn convert_mdc_to_md_per_glob(
+    content: &str,
+    relative_path: &Path,
+    targets: &[PathBuf],
+    options: &ConvertOptions,
+) -> Result<Converted> {
+    let parts = split_by_glob(content, options, Some(relative_path))?;
+    if parts.len() < 2 {
+        return convert_mdc_to_md(content, relative_path, targets, options);
+    }
+
+    let extension = options.instructions_suffix();
+    let mut used_slugs = HashSet::new();
+    let mut converted = Converted::default();
+    for (glob, output_content) in parts {
+        let base_slug = glob_slug(&glob);
+        let mut slug = base_slug.clone();
+        let mut suffix = 2;
+        while !used_slugs.insert(slug.clone()) {
+            slug = format!("{}-{}", base_slug, suffix);
+            suffix += 1;
+        }
+
+        let output_content = with_source_body(content, output_content, options);
+        for target in targets {
+            let file_name = target
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("file");
+            let stem = file_name.strip_suffix(extension).unwrap_or(file_name);
+            let glob_target = target.with_file_name(format!("{}.{}{}", stem, slug, extension));
+            let written = write_target(&glob_target, &output_content, options)?;
+            converted.push(glob_target, written);
+        }
+    }
+
+    Ok(converted)
+}
 
+// File stem collecting rules that apply everywhere or target no known language.
+const GLOBAL_INSTRUCTIONS: &str = "global";
+
+// The rules merged into one `<language>.instructions.md`.
+#[derive(Default)]
+struct LanguageGroup {
+    globs: Vec<String>,
+    sections: Vec<RuleSection>,
+    /// Indices of the contributing sources in the outcome list.
+    sources: Vec<usize>,
+}
+
+// Merges every rule into one instruction file per language detected from its
+// globs. A rule targeting several languages appears in each of their files;
+// `alwaysApply` rules, rules without globs and rules with a glob of no known
+// language (such as `**`) go to `global.instructions.md`, applied to every file.
+fn convert_by_language(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    mut source_files: Vec<PathBuf>,
+    options: &ConvertOptions,
+) -> Result<()> {
+    let text_output = options.format == OutputFormat::Text;
+    source_files.sort();
+
+    let total = source_files.len();
+    let mut outcomes = Vec::new();
+    let mut groups: BTreeMap<&'static str, LanguageGroup> = BTreeMap::new();
     for source_file in source_files {
-        let relative_path = source_file
+        let relative = source_file
             .strip_prefix(from_dir)
-            .with_context(|| "Failed to get relative path")?;
-
-        // Change extension from .mdc/.md to .instructions.md
-        let mut target_path = to_dir.join(relative_path);
-        let file_stem = target_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("file");
-        target_path.set_file_name(format!("{}.instructions.md", file_stem));
-
-        // Create parent directories if they don't exist
-        if let Some(parent) = target_path.parent() {
-            if let Err(e) = fs::create_dir_all(parent) {
-                eprintln!("Error creating directory {}: {}", parent.display(), e);
-                continue;
+            .map_err(|_| RulerError::RelativePath(source_file.clone()))?
+            .to_path_buf();
+        let mut outcome = FileOutcome {
+            source: source_file.clone(),
+            relative: relative.clone(),
+            targets: Vec::new(),
+            error: None,
+            warnings: Vec::new(),
+            not_utf8: false,
+            unchanged: Vec::new(),
+            kept: false,
+            dropped: Vec::new(),
+        };
+
+        match read_language_rule(&source_file, &relative, options) {
+            Ok(rule) => {
+                for (language, globs) in rule.languages {
+                    let group = groups.entry(language).or_default();
+                    for glob in globs {
+                        if !group.globs.contains(&glob) {
+                            group.globs.push(glob);
+                        }
+                    }
+                    group.sections.push(rule.section.clone());
+                    group.sources.push(outcomes.len());
+                }
+                outcome.warnings = rule.warnings;
+                outcome.dropped = rule.dropped;
+            }
+            Err(e) => {
+                if text_output {
+                    let line = format!("Error converting {}: {}", source_file.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
+                outcome.set_error(&e);
             }
         }
+        let abort = options.strict && outcome.has_problems();
+        outcomes.push(outcome);
+        if abort {
+            break;
+        }
+    }
 
-        match convert_mdc_to_md(&source_file, &target_path) {
-            Ok(()) => {
-                println!(
-                    "Converted: {} -> {}",
-                    source_file.display(),
-                    target_path.display()
-                );
-                success_count += 1;
+    for (language, group) in groups {
+        let file_name = format!("{}{}", language, options.instructions_suffix());
+        let meta = GithubMetadata {
+            apply_to: Some(group.globs.join(",")),
+            ..Default::default()
+        };
+        let style = OutputStyle {
+            line_ending: LineEnding::Lf,
+            format: options.frontmatter_format.unwrap_or_default(),
+        };
+        let content = render_github_content(
+            Some(&meta),
+            merge_sections(&group.sections, options.collapsible_sections),
+            style,
+            options,
+        )?;
+
+        for to_dir in to_dirs {
+            let target = to_dir.join(&file_name);
+            let result = write_target(&target, &content, options);
+            if let Err(e) = &result {
+                if text_output && options.check {
+                    eprint_colored(options.color, Color::Red, &e.to_string());
+                } else if text_output {
+                    let line = format!("Error writing {}: {}", target.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
             }
-            Err(e) => {
-                eprintln!("Error converting {}: {}", source_file.display(), e);
-                error_count += 1;
-                continue;
+            for &index in &group.sources {
+                let outcome = &mut outcomes[index];
+                outcome.targets.push(target.clone());
+                match &result {
+                    Ok(false) => outcome.unchanged.push(target.clone()),
+                    Ok(true) => {}
+                    Err(e) => {
+                        outcome.error.get_or_insert_with(|| e.to_string());
+                    }
+                }
             }
         }
     }
 
-    if error_count > 0 {
-        println!(
-            "Conversion completed with {} successes and {} errors.",
-            success_count, error_count
-        );
-    } else {
-        println!("Conversion completed successfully!");
+    if text_output {
+        for outcome in outcomes.iter().filter(|o| o.error.is_none()) {
+            for warning in outcome
+                .warnings
+                .iter()
+                .filter(|_| !options.report_only_errors)
+            {
+                let line = format!("Warning: {}: {}", outcome.source.display(), warning);
+                eprint_colored(options.color, Color::Yellow, &line);
+            }
+            for target in outcome
+                .targets
+                .iter()
+                .filter(|_| !options.check && !options.quiet)
+            {
+                print_target(
+                    &outcome.source,
+                    target,
+                    outcome.unchanged.contains(target),
+                    options,
+                );
+            }
+        }
     }
-    Ok(())
+
+    let skipped = total - outcomes.len();
+    if options.prune && skipped == 0 {
+        for to_dir in to_dirs {
+            prune_orphans(to_dir, options.instructions_suffix(), &outcomes, options)?;
+        }
+    }
+    finish_batch(&outcomes, skipped, options)
 }
 
-fn convert_mdc_to_md(source: &Path, target: &Path) -> Result<()> {
-    let content = fs::read_to_string(source)
-        .with_context(|| format!("Failed to read file: {}", source.display()))?;
+// One rule read for `--by-language`.
+struct LanguageRule {
+    section: RuleSection,
+    /// The globs the rule contributes to each language's file.
+    languages: Vec<(&'static str, Vec<String>)>,
+    warnings: Vec<String>,
+    /// Fields a merged section has no place for.
+    dropped: Vec<String>,
+}
 
+fn read_language_rule(
+    source_file: &Path,
+    relative: &Path,
+    options: &ConvertOptions,
+) -> Result<LanguageRule> {
+    let content = read_cursor_rule(source_file, options)?;
+    let warnings = lint_cursor_rule(&content, options)?;
     let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(&content)?;
+    let cursor_meta = match frontmatter {
+        Some(fm) => parse_cursor_metadata(&fm, field_info.format)?,
+        None => Default::default(),
+    };
 
-    // Convert Cursor metadata to GitHub metadata
-    let github_metadata = if let Some(fm) = frontmatter {
-        // Try to handle the non-standard YAML format by preprocessing it
-        let preprocessed_fm = preprocess_frontmatter(&fm);
+    let section = RuleSection::from_rule(&cursor_meta, body, relative);
+    let dropped = present_fields(&cursor_meta)
+        .into_iter()
+        .filter(|field| matches!(*field, "authors" | "tags" | "version"))
+        .map(str::to_string)
+        .collect();
+
+    // The global file already covers every language, so a rule that belongs
+    // there is not repeated in the language files
+    let mut languages: Vec<(&'static str, Vec<String>)> = Vec::new();
+    let mut global = cursor_meta.always_apply == Some(true);
+    for glob in cursor_meta.globs.unwrap_or_default() {
+        let detected = glob_languages(&glob);
+        global |= detected.is_empty();
+        for language in detected {
+            match languages.iter_mut().find(|(name, _)| *name == language) {
+                Some((_, globs)) => globs.push(glob.clone()),
+                None => languages.push((language, vec![glob.clone()])),
+            }
+        }
+    }
+    if global || languages.is_empty() {
+        languages = vec![(
+            GLOBAL_INSTRUCTIONS,
+            vec![options.always_apply_glob().to_string()],
+        )];
+    }
+
+    Ok(LanguageRule {
+        section,
+        languages,
+        warnings,
+        dropped,
+    })
+}
 
-        let cursor_meta: CursorMetadata = serde_yaml::from_str(&preprocessed_fm)
-            .with_context(|| format!("Failed to parse Cursor frontmatter after preprocessing: {}", preprocessed_fm))?;
+// Turns a glob into a filename-safe slug, e.g. `src/**/*.ts` -> `src-ts`.
+fn glob_slug(glob: &str) -> String {
+    file_slug(glob, "glob")
+}
+
+/// Converts the raw contents of a Cursor rule into GitHub Copilot instruction contents.
+pub fn convert_cursor_content(content: &str) -> Result<String> {
+    convert_cursor_content_with_options(content, &ConvertOptions::default(), None)
+}
+
+/// Like [`convert_cursor_content`], honoring the content-level `options`.
+/// `rule_path` is the rule's path relative to its source directory, used for
+/// placeholders such as `{path}` in `--default-description`.
+pub fn convert_cursor_content_with_options(
+    content: &str,
+    options: &ConvertOptions,
+    rule_path: Option<&Path>,
+) -> Result<String> {
+    let (github_metadata, body, style) =
+        cursor_content_to_github_metadata(content, options, rule_path)?;
+    render_github_content(github_metadata.as_ref(), body, style, options)
+}
+
+/// Converts a Cursor rule into one GitHub instruction per glob, returning
+/// `(glob, contents)` pairs that share the same body. Rules that are always
+/// applied or have no globs produce an empty list.
+pub fn split_cursor_content_by_glob(content: &str) -> Result<Vec<(String, String)>> {
+    split_by_glob(content, &ConvertOptions::default(), None)
+}
+
+fn split_by_glob(
+    content: &str,
+    options: &ConvertOptions,
+    rule_path: Option<&Path>,
+) -> Result<Vec<(String, String)>> {
+    let (github_metadata, body, style) =
+        cursor_content_to_github_metadata(content, options, rule_path)?;
+
+    let meta = match github_metadata {
+        Some(meta) => meta,
+        None => return Ok(Vec::new()),
+    };
+    let globs: Vec<String> = match &meta.apply_to {
+        Some(apply_to) if apply_to != options.always_apply_glob() && !apply_to.is_empty() => {
+            split_globs(apply_to)
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+        }
+        _ => return Ok(Vec::new()),
+    };
+
+    globs
+        .into_iter()
+        .map(|glob| {
+            let mut glob_meta = meta.clone();
+            glob_meta.apply_to = Some(glob.clone());
+            let output_content =
+                render_github_content(Some(&glob_meta), body.clone(), style, options)?;
+            Ok((glob, output_content))
+        })
+        .collect()
+}
+
+// Renders an instruction from its metadata and body. With `--no-frontmatter`
+// the metadata is dropped and its name and description open the body instead.
+// A `--template` replaces the usual layout: its `{{frontmatter}}` becomes the
+// delimited frontmatter block, or nothing for an instruction without
+// metadata, and its `{{body}}` the body.
+fn render_github_content(
+    meta: Option<&GithubMetadata>,
+    body: String,
+    style: OutputStyle,
+    options: &ConvertOptions,
+) -> Result<String> {
+    let (meta, body) = match meta {
+        Some(meta) if options.no_frontmatter => (None, heading_body(meta, body, style)),
+        meta => (meta, body),
+    };
+    let frontmatter = match meta {
+        Some(meta) => {
+            let delimiter = style.format.delimiter();
+            Some(style.line_ending.apply(&format!(
+                "{}\n{}{}",
+                delimiter,
+                serialize_github_metadata(meta, style.format)?,
+                delimiter
+            )))
+        }
+        None => None,
+    };
 
-        let github_meta = GithubMetadata {
+    Ok(match (options.template.as_deref(), frontmatter) {
+        (Some(template), frontmatter) => {
+            let rendered = fill_template(
+                &style.line_ending.apply(template),
+                frontmatter.as_deref().unwrap_or_default(),
+                &body,
+            );
+            if frontmatter.is_some() {
+                rendered
+            } else {
+                rendered.trim_start().to_string()
+            }
+        }
+        (None, Some(frontmatter)) => {
+            format!("{}{}{}", frontmatter, style.line_ending.apply("\n\n"), body)
+        }
+        (None, None) => body,
+    })
+}
+
+// Fills a `--template`'s `{{frontmatter}}` and `{{body}}` in one pass, so a
+// placeholder written in the frontmatter or body is kept as text.
+fn fill_template(template: &str, frontmatter: &str, body: &str) -> String {
+    let mut out = String::with_capacity(template.len() + frontmatter.len() + body.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("{{frontmatter}}") {
+            out.push_str(frontmatter);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{{body}}") {
+            out.push_str(body);
+            rest = after;
+        } else {
+            out.push('{');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// `# {name}`, then the description as a paragraph, then `body`; a missing or
+// empty name or description is left out.
+fn heading_body(meta: &GithubMetadata, body: String, style: OutputStyle) -> String {
+    let mut header = String::new();
+    if let Some(name) = meta
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        header.push_str(&format!("# {}\n\n", name));
+    }
+    let description = meta.description.as_deref().map(str::trim);
+    if let Some(description) = description.filter(|d| !d.is_empty()) {
+        header.push_str(&format!("{}\n\n", description));
+    }
+    format!("{}{}", style.line_ending.apply(&header), body)
+}
+
+// Line ending and frontmatter syntax to write the converted instruction with.
+#[derive(Clone, Copy)]
+struct OutputStyle {
+    line_ending: LineEnding,
+    format: FrontmatterFormat,
+}
+
+fn cursor_content_to_github_metadata(
+    content: &str,
+    options: &ConvertOptions,
+    rule_path: Option<&Path>,
+) -> Result<(Option<GithubMetadata>, String, OutputStyle)> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+
+    // Convert Cursor metadata to GitHub metadata
+    let mut github_metadata = if let Some(fm) = frontmatter {
+        debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
+        let cursor_meta = parse_cursor_metadata(&fm, field_info.format)?;
+        debug!("parsed {:?}", cursor_meta);
+        let manual = cursor_meta.always_apply == Some(false);
+        // `lint_cursor_rule` warns about these
+        let mut extra = cursor_meta.github.unwrap_or_default();
+        extra.retain(|key, _| !GITHUB_FIELDS.contains(&key.as_str()));
+
+        let mut github_meta = GithubMetadata {
             description: cursor_meta.description,
             apply_to: if cursor_meta.always_apply == Some(true) {
-                Some("**".to_string())
-            } else if let Some(globs) = cursor_meta.globs {
+                match &cursor_meta.globs {
+                    Some(globs) if !globs.is_empty() => debug!(
+                        "alwaysApply: true overrides globs {:?}; applyTo: {:?}",
+                        globs,
+                        options.always_apply_glob()
+                    ),
+                    _ => debug!(
+                        "alwaysApply: true; applyTo: {:?}",
+                        options.always_apply_glob()
+                    ),
+                }
+                Some(options.always_apply_glob().to_string())
+            } else if let Some(globs) = cursor_meta.globs.as_deref().map(normalize_globs) {
                 if !globs.is_empty() {
                     // Convert .mdc extensions to .instructions.md
                     let converted_globs: Vec<String> = globs.iter()
                         .map(|glob| {
                             if glob.ends_with(".mdc") {
-                                glob.replace(".mdc", ".instructions.md")
+                                glob.replace(".mdc", options.instructions_suffix())
                             } else {
                                 glob.clone()
                             }
                         })
                         .collect();
+                    debug!(
+                        "globs {:?} mapped to applyTo: {:?}",
+                        globs,
+                        converted_globs.join(",")
+                    );
                     Some(converted_globs.join(","))
                 } else {
+                    debug!("globs is empty; applyTo omitted");
                     None
                 }
             } else {
+                debug!("no globs and alwaysApply is not true; applyTo omitted");
                 None
             },
+            name: cursor_meta.name,
+            authors: cursor_meta.authors,
+            tags: if options.normalize_tags {
+                cursor_meta.tags.as_deref().map(normalize_tags)
+            } else {
+                cursor_meta.tags
+            },
+            version: cursor_meta.version,
+            priority: cursor_meta.priority,
             description_present: field_info.description_present,
             apply_to_present: field_info.globs_present,
+            description_block: field_info.description_block,
+            comments: field_info.comments.clone(),
+            key_order: field_info.key_order.clone(),
+            extra,
         };
 
+        // Some Copilot clients apply an instruction without `applyTo` to
+        // every file, so a rule that explicitly never attaches on its own
+        // gets a pattern that matches nothing
+        if manual && github_meta.apply_to.is_none() {
+            debug!("alwaysApply: false without globs; applyTo: \"\"");
+            github_meta.apply_to = Some(String::new());
+            github_meta.apply_to_present = false;
+        }
+
         Some(github_meta)
     } else {
+        debug!("no frontmatter detected");
         None
     };
 
-    // Write the converted file
-    let output_content = if let Some(meta) = github_metadata {
-        let frontmatter_yaml = serialize_github_metadata(&meta);
-        format!("---\n{}---\n\n{}", frontmatter_yaml, body)
-    } else {
-        body
+    if options.infer_description {
+        if let Some(title) = first_heading(&body) {
+            let meta = github_metadata.get_or_insert_with(GithubMetadata::default);
+            if meta.description.as_deref().is_none_or(str::is_empty) {
+                meta.description = Some(title);
+            }
+        }
+    }
+
+    if let Some(template) = &options.default_description {
+        let meta = github_metadata.get_or_insert_with(GithubMetadata::default);
+        if meta.description.as_deref().is_none_or(str::is_empty) {
+            meta.description = Some(render_description_template(
+                template,
+                meta.name.as_deref(),
+                rule_path,
+            ));
+        }
+    }
+
+    let style = OutputStyle {
+        line_ending: field_info.line_ending,
+        format: options.frontmatter_format.unwrap_or(field_info.format),
     };
+    Ok((github_metadata, body, style))
+}
 
-    fs::write(target, output_content)
-        .with_context(|| format!("Failed to write file: {}", target.display()))?;
+// Fills `{name}` (the rule's `name`, else its file stem) and `{path}` (its
+// path relative to the source directory) in a `--default-description` template.
+fn render_description_template(
+    template: &str,
+    name: Option<&str>,
+    rule_path: Option<&Path>,
+) -> String {
+    let name = name
+        .map(str::to_string)
+        .or_else(|| {
+            rule_path
+                .and_then(|p| p.file_stem())
+                .map(|s| s.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "rule".to_string());
+    let path = rule_path
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
 
-    Ok(())
+    template.replace("{name}", &name).replace("{path}", &path)
 }
 
-fn serialize_github_metadata(meta: &GithubMetadata) -> String {
-    let mut yaml = String::new();
+// Writes the frontmatter lines by hand so quoting stays stable. Both syntaxes
+// share the same quoted-string and inline-list forms; only the separator and
+// the empty-field form differ.
+fn serialize_github_metadata(meta: &GithubMetadata, format: FrontmatterFormat) -> Result<String> {
+    let mut out = String::new();
+    let (sep, empty) = match format {
+        FrontmatterFormat::Yaml => (":", ""),
+        FrontmatterFormat::Toml => (" =", " \"\""),
+    };
 
+    // A description written as a YAML block scalar stays one
+    let description = |desc: &str| match format {
+        FrontmatterFormat::Yaml if meta.description_block => {
+            literal_block(desc).unwrap_or_else(|| quote_string(desc))
+        }
+        _ => quote_string(desc),
+    };
     if meta.description_present {
         if let Some(desc) = &meta.description {
             if desc.is_empty() {
-                yaml.push_str("description:\n");
+                out.push_str(&format!("description{}{}\n", sep, empty));
             } else {
-                yaml.push_str(&format!("description: \"{}\"\n", desc));
+                out.push_str(&format!("description{} {}\n", sep, description(desc)));
             }
         } else {
-            yaml.push_str("description:\n");
+            out.push_str(&format!("description{}{}\n", sep, empty));
         }
     } else if meta.description.is_some() {
-        yaml.push_str(&format!("description: \"{}\"\n", meta.description.as_ref().unwrap()));
+        out.push_str(&format!(
+            "description{} {}\n",
+            sep,
+            description(meta.description.as_ref().unwrap())
+        ));
     }
 
     if meta.apply_to_present {
         if let Some(apply_to) = &meta.apply_to {
             if apply_to.is_empty() {
-                yaml.push_str("applyTo:\n");
+                out.push_str(&format!("applyTo{}{}\n", sep, empty));
             } else {
-                yaml.push_str(&format!("applyTo: \"{}\"\n", apply_to));
+                out.push_str(&format!("applyTo{} {}\n", sep, quote_string(apply_to)));
             }
         } else {
-            yaml.push_str("applyTo:\n");
+            out.push_str(&format!("applyTo{}{}\n", sep, empty));
         }
     } else if meta.apply_to.is_some() {
-        yaml.push_str(&format!("applyTo: \"{}\"\n", meta.apply_to.as_ref().unwrap()));
+        out.push_str(&format!(
+            "applyTo{} {}\n",
+            sep,
+            quote_string(meta.apply_to.as_ref().unwrap())
+        ));
+    }
+
+    // Cursor-only fields, emitted so g2c can restore them
+    if let Some(name) = &meta.name {
+        out.push_str(&format!("name{} {}\n", sep, quote_string(name)));
+    }
+    if let Some(authors) = &meta.authors {
+        out.push_str(&format!("authors{} {}\n", sep, format_string_list(authors)));
+    }
+    if let Some(tags) = &meta.tags {
+        out.push_str(&format!("tags{} {}\n", sep, format_string_list(tags)));
+    }
+    if let Some(version) = &meta.version {
+        out.push_str(&format!("version{} {}\n", sep, quote_string(version)));
+    }
+    if let Some(priority) = meta.priority {
+        out.push_str(&format!("priority{} {}\n", sep, priority));
+    }
+    // GitHub-only keys g2c kept in a `github:` block go back to the top level
+    if !meta.extra.is_empty() {
+        out.push_str(&serialize_frontmatter(&meta.extra, format, "GitHub")?);
+    }
+
+    let renames = [("globs", "applyTo"), ("alwaysApply", "applyTo")];
+    Ok(restore_comments(
+        &reorder_fields(&out, &meta.key_order, &renames),
+        &meta.comments,
+        &renames,
+    ))
+}
+
+fn format_string_list(items: &[String]) -> String {
+    let quoted: Vec<String> = items.iter().map(|item| quote_string(item)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_cursor_content, convert_cursor_content_with_options, convert_cursor_to_github,
+        convert_cursor_to_github_targets, convert_into, dropped_fields, glob_slug,
+    };
+    use crate::error::RulerError;
+    use crate::parser::batch::BatchSources;
+    use crate::parser::changelog::read_manifest;
+    use crate::parser::g2c::convert_github_content;
+    use crate::parser::options::{ConvertOptions, Eol, FrontmatterFormat};
+    use crate::parser::upgrade::upgrade_globs_content;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_convert_cursor_content_maps_globs_to_apply_to() {
+        let input = "---\ndescription: \"Style\"\nglobs: [\"*.ts\", \"*.tsx\"]\n---\n\nUse const.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Style\"\napplyTo: \"*.ts,*.tsx\"\n---\n\nUse const."
+        );
+    }
+
+    #[test]
+    fn test_duplicate_and_padded_globs_are_joined_once() {
+        let input = "---\nglobs: [\"src/**\", \" src/** \", \"src/**,src/**\", \"docs/** \", \"\"]\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(output, "---\napplyTo: \"src/**,docs/**\"\n---\n\nBody.");
+    }
+
+    #[test]
+    fn test_manual_rule_without_globs_applies_to_no_files() {
+        let input = "---\ndescription: \"On request\"\nalwaysApply: false\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"On request\"\napplyTo: \"\"\n---\n\nBody."
+        );
+
+        // Without an explicit `alwaysApply: false`, applyTo stays omitted
+        let output = convert_cursor_content("---\ndescription: \"x\"\n---\n\nBody.").unwrap();
+        assert_eq!(output, "---\ndescription: \"x\"\n---\n\nBody.");
     }
 
-    yaml
+    #[test]
+    fn test_indented_block_list_globs_round_trip() {
+        let input =
+            "---\ndescription: \"Block\"\nglobs:\n  - \"src/**\"\n  - \"tests/**\"\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Block\"\napplyTo: \"src/**,tests/**\"\n---\n\nBody."
+        );
+
+        let input = "---\r\nglobs:\r\n    - src/**\r\n    # and the tests\r\n    - tests/**\r\n---\r\n\r\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert!(output.starts_with("---\r\napplyTo: \"src/**,tests/**\"\r\n"));
+
+        // A comment with a comma after `globs:` is not a comma-separated
+        // value for the preprocessor (or `upgrade`) to rewrite
+        let input = "---\nglobs: # source, tests\n  - src/**\n  - tests/**\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert!(output.contains("applyTo: \"src/**,tests/**\"\n"));
+        assert_eq!(upgrade_globs_content(input), None);
+    }
+
+    #[test]
+    fn test_dropped_fields_are_those_the_output_leaves_out() {
+        let rule = "---\nname: Style\ndescription: \"Code style\"\nglobs: \"*.ts\"\ntags: [ts]\nversion: \"1\"\n---\n\nBody.";
+        assert!(dropped_fields(rule, &ConvertOptions::default())
+            .unwrap()
+            .is_empty());
+
+        let options = ConvertOptions {
+            no_frontmatter: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            dropped_fields(rule, &options).unwrap(),
+            ["globs", "tags", "version"]
+        );
+
+        let options = ConvertOptions {
+            template: Some("{{body}}\n".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            dropped_fields(rule, &options).unwrap(),
+            ["name", "description", "globs", "tags", "version"]
+        );
+    }
+
+    #[test]
+    fn test_convert_cursor_content_without_frontmatter() {
+        let output = convert_cursor_content("Just a body.").unwrap();
+        assert_eq!(output, "Just a body.");
+    }
+
+    #[test]
+    fn test_fully_crlf_rule_round_trips_without_touching_the_body() {
+        let input = "---\r\n# Owner: platform\r\ndescription: |\r\n  First line.\r\n  ---\r\nglobs: \"*.cs\"\r\n---\r\n\r\nAbove.\r\n\r\n---\r\n\r\nBelow.\r\n";
+        let github = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            github,
+            "---\r\n# Owner: platform\r\ndescription: |\r\n  First line.\r\n  ---\r\napplyTo: \"*.cs\"\r\n---\r\n\r\nAbove.\r\n\r\n---\r\n\r\nBelow."
+        );
+        let cursor = crate::parser::convert_github_content(&github).unwrap();
+        assert!(cursor.ends_with("---\r\n\r\nAbove.\r\n\r\n---\r\n\r\nBelow."));
+        assert!(!cursor.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn test_template_wraps_frontmatter_and_body() {
+        let options = ConvertOptions {
+            template: Some(
+                "{{frontmatter}}\n\n<!-- Auto-generated, do not edit -->\n\n{{body}}\n\n<!-- end -->\n"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+        let output = convert_cursor_content_with_options(
+            "---\nglobs: \"*.rs\"\n---\n\nUse clippy.",
+            &options,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "---\napplyTo: \"*.rs\"\n---\n\n<!-- Auto-generated, do not edit -->\n\nUse clippy.\n\n<!-- end -->\n"
+        );
+
+        let output = convert_cursor_content_with_options("Just a body.", &options, None).unwrap();
+        assert_eq!(
+            output,
+            "<!-- Auto-generated, do not edit -->\n\nJust a body.\n\n<!-- end -->\n"
+        );
+
+        // Placeholders the rule itself contains are left as written
+        let output = convert_cursor_content_with_options(
+            "---\ndescription: \"Use {{body}}\"\n---\n\nKeep {{frontmatter}}.",
+            &options,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Use {{body}}\"\n---\n\n<!-- Auto-generated, do not edit -->\n\nKeep {{frontmatter}}.\n\n<!-- end -->\n"
+        );
+    }
+
+    #[test]
+    fn test_no_frontmatter_promotes_name_and_description_to_heading() {
+        let options = ConvertOptions {
+            no_frontmatter: true,
+            ..Default::default()
+        };
+        let convert =
+            |content: &str| convert_cursor_content_with_options(content, &options, None).unwrap();
+        assert_eq!(
+            convert("---\nname: Rust style\ndescription: \"Conventions for Rust\"\nglobs: \"*.rs\"\n---\n\nUse clippy."),
+            "# Rust style\n\nConventions for Rust\n\nUse clippy."
+        );
+        assert_eq!(
+            convert("---\ndescription: \"Conventions\"\n---\n\nUse clippy."),
+            "Conventions\n\nUse clippy."
+        );
+        assert_eq!(
+            convert("---\r\nname: Style\r\n---\r\n\r\nBody.\r\n"),
+            "# Style\r\n\r\nBody."
+        );
+        assert_eq!(convert("Just a body."), "Just a body.");
+    }
+
+    #[test]
+    fn test_convert_cursor_content_preserves_crlf() {
+        let input = "---\r\ndescription: \"Windows\"\r\nglobs: \"*.cs\"\r\n---\r\n\r\nLine one.\r\nLine two.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\r\ndescription: \"Windows\"\r\napplyTo: \"*.cs\"\r\n---\r\n\r\nLine one.\r\nLine two."
+        );
+    }
+
+    #[test]
+    fn test_convert_cursor_content_strips_bom() {
+        let input = "\u{feff}---\ndescription: \"Style\"\nglobs: \"*.ts\"\n---\n\nUse const.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Style\"\napplyTo: \"*.ts\"\n---\n\nUse const."
+        );
+        assert_eq!(
+            convert_cursor_content("\u{feff}Just a body.").unwrap(),
+            "Just a body."
+        );
+    }
+
+    #[test]
+    fn test_convert_cursor_content_toml_frontmatter() {
+        let input = "+++\ndescription = \"Style\"\nglobs = [\"*.ts\", \"*.tsx\"]\ntags = [\"web\"]\n+++\n\nUse const.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "+++\ndescription = \"Style\"\napplyTo = \"*.ts,*.tsx\"\ntags = [\"web\"]\n+++\n\nUse const."
+        );
+
+        let options = ConvertOptions {
+            frontmatter_format: Some(FrontmatterFormat::Yaml),
+            ..Default::default()
+        };
+        let output = convert_cursor_content_with_options(input, &options, None).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Style\"\napplyTo: \"*.ts,*.tsx\"\ntags: [\"web\"]\n---\n\nUse const."
+        );
+    }
+
+    #[test]
+    fn test_description_with_quotes_and_colon_round_trips() {
+        let input = "---\ndescription: 'He said \"hi\": done'\nglobs: \"*.md\"\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert!(output.contains("description: \"He said \\\"hi\\\": done\"\n"));
+
+        let restored = crate::parser::convert_github_content(&output).unwrap();
+        assert!(restored.starts_with("---\ndescription: 'He said \"hi\": done'\n"));
+
+        let multiline = "---\ndescription: \"Line one\\nLine two — ünïcode\"\n---\n\nBody.";
+        let output = convert_cursor_content(multiline).unwrap();
+        assert!(output.contains("description: \"Line one\\nLine two — ünïcode\"\n"));
+    }
+
+    #[test]
+    fn test_output_keeps_source_key_order() {
+        let input = "---\nglobs: \"*.rs\"\nalwaysApply: false\ntags: [\"rust\"]\ndescription: \"Rust\"\nname: \"rust\"\n---\n\nBody.";
+        let github = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            github,
+            "---\napplyTo: \"*.rs\"\ntags: [\"rust\"]\ndescription: \"Rust\"\nname: \"rust\"\n---\n\nBody."
+        );
+
+        let cursor = convert_github_content(&github).unwrap();
+        assert_eq!(
+            cursor,
+            "---\nglobs:\n- '*.rs'\nalwaysApply: false\ntags:\n- rust\ndescription: Rust\nname: rust\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_comments_survive_round_trip() {
+        let input = "---\n# Shown in the rule picker\ndescription: \"API rules\"\n# TODO: drop legacy/ once the migration lands\nglobs: [\"src/api/**\", \"legacy/**\"]\nnotes: |\n  # not a comment\n# trailing note\n---\n\nBody.";
+        let github = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            github,
+            "---\n# Shown in the rule picker\ndescription: \"API rules\"\n# TODO: drop legacy/ once the migration lands\napplyTo: \"src/api/**,legacy/**\"\n# trailing note\n---\n\nBody."
+        );
+
+        let cursor = crate::parser::convert_github_content(&github).unwrap();
+        assert!(cursor.contains(
+            "# TODO: drop legacy/ once the migration lands\nglobs:\n- src/api/**\n- legacy/**\n"
+        ));
+        assert!(cursor.starts_with("---\n# Shown in the rule picker\ndescription: API rules\n"));
+    }
+
+    #[test]
+    fn test_block_scalar_description_survives_round_trip() {
+        let input = "---\ndescription: |\n  Line one: with a colon\n\n  globs: not a field\nglobs: \"*.rs\"\n---\n\nBody.";
+        let github = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            github,
+            "---\ndescription: |\n  Line one: with a colon\n\n  globs: not a field\napplyTo: \"*.rs\"\n---\n\nBody."
+        );
+
+        let cursor = convert_github_content(&github).unwrap();
+        assert_eq!(
+            cursor,
+            "---\ndescription: |\n  Line one: with a colon\n\n  globs: not a field\nglobs:\n- '*.rs'\nalwaysApply: false\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_scalar_description_is_kept_as_text() {
+        for (value, expected) in [("42", "42"), ("true", "true"), ("1.5", "1.5")] {
+            let input = format!("---\ndescription: {}\n---\n\nBody.", value);
+            let output = convert_cursor_content(&input).unwrap();
+            assert!(
+                output.contains(&format!("description: \"{}\"\n", expected)),
+                "{}",
+                output
+            );
+        }
+    }
+
+    #[test]
+    fn test_github_block_cannot_repeat_modeled_keys() {
+        let input =
+            "---\nglobs: \"*.py\"\ngithub:\n  applyTo: \"**\"\n  title: Python\n---\n\nBody.";
+        assert_eq!(
+            convert_cursor_content(input).unwrap(),
+            "---\napplyTo: \"*.py\"\ntitle: Python\n---\n\nBody."
+        );
+
+        // TOML has no null, so the block can't be written and the rule fails
+        let input = "---\ngithub:\n  owner: null\n---\n\nBody.";
+        let options = ConvertOptions {
+            frontmatter_format: Some(FrontmatterFormat::Toml),
+            ..Default::default()
+        };
+        assert!(matches!(
+            convert_cursor_content_with_options(input, &options, None),
+            Err(RulerError::Serialize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_list_valued_description_is_joined() {
+        let input =
+            "---\ndescription:\n  - Prefer const\n  - Avoid any\nglobs: \"*.ts\"\n---\n\nBody.";
+        let output = convert_cursor_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Prefer const\\nAvoid any\"\napplyTo: \"*.ts\"\n---\n\nBody."
+        );
+
+        let error = convert_cursor_content("---\ndescription: {a: 1}\n---\n\nBody.").unwrap_err();
+        match error {
+            RulerError::Parse { kind, source } => {
+                assert_eq!(kind, "Cursor");
+                assert!(source
+                    .to_string()
+                    .contains("a description string or a list of strings"));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_glob_slug() {
+        assert_eq!(glob_slug("*.ts"), "ts");
+        assert_eq!(glob_slug("src/**/*.rs"), "src-rs");
+        assert_eq!(glob_slug("**"), "glob");
+    }
+
+    #[test]
+    fn test_one_glob_per_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("style.mdc"),
+            "---\ndescription: \"Style\"\nglobs: [\"*.ts\", \"src/**/*.ts\", \"**/*.ts\"]\n---\n\nBody.",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            one_glob_per_file: true,
+            ..Default::default()
+        };
+        convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+
+        let first = fs::read_to_string(to_dir.join("style.ts.instructions.md")).unwrap();
+        assert!(first.contains("applyTo: \"*.ts\"\n"));
+        assert!(first.ends_with("Body.\n"));
+        let second = fs::read_to_string(to_dir.join("style.src-ts.instructions.md")).unwrap();
+        assert!(second.contains("applyTo: \"src/**/*.ts\"\n"));
+        // `**/*.ts` slugs to `ts` as well, so it gets a numeric suffix
+        let third = fs::read_to_string(to_dir.join("style.ts-2.instructions.md")).unwrap();
+        assert!(third.contains("applyTo: \"**/*.ts\"\n"));
+        assert!(!to_dir.join("style.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_by_language_merges_rules_per_language() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("react.mdc"),
+            "---\ndescription: \"React\"\nglobs: [\"src/**/*.tsx\"]\n---\n\nUse hooks.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("strict.mdc"),
+            "---\ndescription: \"Strict types\"\nglobs: [\"**/*.ts\", \"**/*.py\"]\npriority: 5\n---\n\nNo implicit any.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("pep8.mdc"),
+            "---\nglobs: \"*.py\"\n---\n\nFollow PEP 8.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("tone.mdc"),
+            "---\ndescription: \"Tone\"\nalwaysApply: true\n---\n\nBe concise.",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            by_language: true,
+            ..Default::default()
+        };
+        convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+
+        // Higher priority sections come first
+        assert_eq!(
+            fs::read_to_string(to_dir.join("typescript.instructions.md")).unwrap(),
+            "---\napplyTo: \"src/**/*.tsx,**/*.ts\"\n---\n\n## Strict types\n\nNo implicit any.\n\n## React\n\nUse hooks.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(to_dir.join("python.instructions.md")).unwrap(),
+            "---\napplyTo: \"*.py,**/*.py\"\n---\n\n## Strict types\n\nNo implicit any.\n\n## pep8\n\nFollow PEP 8.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(to_dir.join("global.instructions.md")).unwrap(),
+            "---\napplyTo: \"**\"\n---\n\n## Tone\n\nBe concise.\n"
+        );
+        assert!(!to_dir.join("react.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_excluded_sources_are_not_converted() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("style.mdc"),
+            "---\nglobs: \"*.ts\"\n---\n\nStyle.",
+        )
+        .unwrap();
+        // Malformed, so converting it would be reported as an error
+        fs::write(
+            from_dir.join("wip.draft.mdc"),
+            "---\nglobs: [unclosed\n---\n\nDraft.",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            exclude: vec!["*.draft.mdc".to_string()],
+            format: crate::parser::OutputFormat::Json,
+            ..Default::default()
+        };
+        convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+
+        assert!(to_dir.join("style.instructions.md").exists());
+        assert!(!to_dir.join("wip.draft.instructions.md").exists());
+        assert_eq!(fs::read_dir(&to_dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_rename_map_overrides_target_names() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(from_dir.join("lang")).unwrap();
+        fs::write(from_dir.join("style.mdc"), "Style.").unwrap();
+        fs::write(from_dir.join("lang/rust.mdc"), "Rust.").unwrap();
+        fs::write(from_dir.join("other.mdc"), "Other.").unwrap();
+
+        let options = ConvertOptions {
+            rename_map: [
+                (
+                    "style.mdc".into(),
+                    "copilot-style.instructions.md".to_string(),
+                ),
+                (
+                    "lang/rust.mdc".into(),
+                    "rustlang.instructions.md".to_string(),
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+        assert!(to_dir.join("copilot-style.instructions.md").exists());
+        assert!(to_dir.join("lang/rustlang.instructions.md").exists());
+        assert!(to_dir.join("other.instructions.md").exists());
+        assert!(!to_dir.join("style.instructions.md").exists());
+
+        let back = temp.path().join("back");
+        let options = ConvertOptions {
+            rename_map: [(
+                "copilot-style.instructions.md".into(),
+                "style.mdc".to_string(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        crate::parser::convert_github_to_cursor(&to_dir, &back, &options).unwrap();
+        assert!(back.join("style.mdc").exists());
+        assert!(back.join("lang/rustlang.mdc").exists());
+    }
+
+    #[test]
+    fn test_targets_end_with_exactly_one_newline() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(&from_dir).unwrap();
+        let sources = [
+            ("none", "---\nglobs: \"*.rs\"\n---\n\nBody.", "Body.\n"),
+            ("one", "---\nglobs: \"*.rs\"\n---\n\nBody.\n", "Body.\n"),
+            (
+                "many",
+                "---\nglobs: \"*.rs\"\n---\n\nBody.\n\n\n",
+                "Body.\n",
+            ),
+            (
+                "crlf",
+                "---\r\nglobs: \"*.rs\"\r\n---\r\n\r\nBody.",
+                "Body.\r\n",
+            ),
+            ("plain", "Body.\n\n", "Body.\n"),
+        ];
+        for (name, content, _) in sources {
+            fs::write(from_dir.join(format!("{}.mdc", name)), content).unwrap();
+        }
+
+        convert_cursor_to_github(&from_dir, &to_dir, &ConvertOptions::default()).unwrap();
+        for (name, _, ending) in sources {
+            let target = fs::read(to_dir.join(format!("{}.instructions.md", name))).unwrap();
+            let target = String::from_utf8(target).unwrap();
+            assert!(target.ends_with(ending), "{}: {:?}", name, target);
+        }
+    }
+
+    #[test]
+    fn test_eol_sets_the_written_line_endings() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("lf.mdc"),
+            "---\nglobs: \"*.rs\"\n---\n\nOne.\nTwo.\n",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("crlf.mdc"),
+            "---\r\nglobs: \"*.rs\"\r\n---\r\n\r\nOne.\r\nTwo.\r\n",
+        )
+        .unwrap();
+
+        let lf = "---\napplyTo: \"*.rs\"\n---\n\nOne.\nTwo.\n";
+        let crlf = "---\r\napplyTo: \"*.rs\"\r\n---\r\n\r\nOne.\r\nTwo.\r\n";
+        for (eol, from_lf, from_crlf) in [
+            (Eol::Keep, lf, crlf),
+            (Eol::Lf, lf, lf),
+            (Eol::Crlf, crlf, crlf),
+        ] {
+            let to_dir = temp.path().join(format!("{:?}", eol));
+            let options = ConvertOptions {
+                eol,
+                ..Default::default()
+            };
+            convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+            let written = |name| fs::read(to_dir.join(name)).unwrap();
+            assert_eq!(
+                written("lf.instructions.md"),
+                from_lf.as_bytes(),
+                "{:?}",
+                eol
+            );
+            assert_eq!(
+                written("crlf.instructions.md"),
+                from_crlf.as_bytes(),
+                "{:?}",
+                eol
+            );
+        }
+    }
+
+    #[test]
+    fn test_flatten_drops_source_subdirectories() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(from_dir.join("frontend/react")).unwrap();
+        fs::write(from_dir.join("top.mdc"), "Top.").unwrap();
+        fs::write(from_dir.join("frontend/react/hooks.mdc"), "Hooks.").unwrap();
+
+        let mirrored = temp.path().join("mirrored");
+        convert_cursor_to_github(&from_dir, &mirrored, &ConvertOptions::default()).unwrap();
+        assert!(mirrored.join("top.instructions.md").exists());
+        assert!(mirrored
+            .join("frontend/react/hooks.instructions.md")
+            .exists());
+
+        let flat = temp.path().join("flat");
+        let options = ConvertOptions {
+            flatten: true,
+            ..Default::default()
+        };
+        convert_cursor_to_github(&from_dir, &flat, &options).unwrap();
+        assert!(flat.join("top.instructions.md").exists());
+        assert!(flat.join("hooks.instructions.md").exists());
+        assert!(!flat.join("frontend").exists());
+
+        // Two rules with the same file name would overwrite each other
+        fs::create_dir_all(from_dir.join("backend")).unwrap();
+        fs::write(from_dir.join("backend/hooks.mdc"), "Webhooks.").unwrap();
+        let collided = temp.path().join("collided");
+        let err = convert_cursor_to_github(&from_dir, &collided, &options).unwrap_err();
+        assert!(matches!(err, RulerError::Failed(2)));
+        assert!(collided.join("top.instructions.md").exists());
+        assert!(!collided.join("hooks.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_instructions_named_sources_keep_their_name() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(from_dir.join("foo.md"), "Foo.").unwrap();
+        fs::write(from_dir.join("bar.instructions.md"), "Bar.").unwrap();
+
+        let to_dir = temp.path().join("out");
+        convert_cursor_to_github(&from_dir, &to_dir, &ConvertOptions::default()).unwrap();
+        assert!(to_dir.join("foo.instructions.md").exists());
+        assert!(to_dir.join("bar.instructions.md").exists());
+        assert!(!to_dir.join("bar.instructions.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_multiple_targets_each_receive_every_rule() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(from_dir.join("lang")).unwrap();
+        fs::write(from_dir.join("style.mdc"), "Style.").unwrap();
+        fs::write(
+            from_dir.join("lang/rust.mdc"),
+            "---\nglobs: \"*.rs\"\n---\n\nRust.",
+        )
+        .unwrap();
+
+        let to_dirs = [temp.path().join("github"), temp.path().join("mirror")];
+        let manifest = temp.path().join("manifest.json");
+        let options = ConvertOptions {
+            manifest: Some(manifest.clone()),
+            ..Default::default()
+        };
+        convert_cursor_to_github_targets(&from_dir, &to_dirs, &options).unwrap();
+
+        // One manifest covers the targets in every directory
+        let manifest = read_manifest(&manifest).unwrap();
+        assert_eq!(manifest.success_count, 2);
+        assert_eq!(manifest.conversions.len(), 4);
+        for to_dir in &to_dirs {
+            assert!(manifest
+                .conversions
+                .iter()
+                .any(|c| c.target == to_dir.join("style.instructions.md").display().to_string()));
+            assert_eq!(
+                fs::read_to_string(to_dir.join("style.instructions.md")).unwrap(),
+                "Style.\n"
+            );
+            assert!(fs::read_to_string(to_dir.join("lang/rust.instructions.md"))
+                .unwrap()
+                .contains("applyTo: \"*.rs\""));
+        }
+    }
+
+    #[test]
+    fn test_changed_rules_collide_with_unchanged_ones() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("out");
+        fs::create_dir_all(from_dir.join("a")).unwrap();
+        fs::create_dir_all(from_dir.join("b")).unwrap();
+        fs::create_dir_all(&to_dir).unwrap();
+        fs::write(from_dir.join("a/style.mdc"), "A.").unwrap();
+        fs::write(from_dir.join("b/style.mdc"), "B.").unwrap();
+        fs::write(to_dir.join("style.instructions.md"), "B.\n").unwrap();
+        fs::write(to_dir.join("old.instructions.md"), "Old.\n").unwrap();
+
+        // As `--watch` converts a saved `a/style.mdc` with `--flatten`
+        let options = ConvertOptions {
+            flatten: true,
+            prune: true,
+            ..Default::default()
+        };
+        let sources = BatchSources {
+            convert: vec![from_dir.join("a/style.mdc")],
+            unchanged: vec![from_dir.join("b/style.mdc")],
+        };
+        let result = convert_into(&from_dir, std::slice::from_ref(&to_dir), sources, &options);
+        assert!(matches!(result, Err(RulerError::Failed(1))));
+        assert_eq!(
+            fs::read_to_string(to_dir.join("style.instructions.md")).unwrap(),
+            "B.\n"
+        );
+        assert!(to_dir.join("old.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_target_extension_names_and_rediscovers_instructions() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("github");
+        let restored_dir = temp.path().join("restored");
+        fs::create_dir_all(from_dir.join("lang")).unwrap();
+        fs::write(
+            from_dir.join("lang/rust.mdc"),
+            "---\nglobs: [\"*.rs\", \"style.mdc\"]\n---\n\nRust.",
+        )
+        .unwrap();
+
+        for suffix in [".md", ".copilot.txt"] {
+            let options = ConvertOptions {
+                target_extension: Some(suffix.to_string()),
+                ..Default::default()
+            };
+            convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+            let target = to_dir.join(format!("lang/rust{}", suffix));
+            assert_eq!(
+                fs::read_to_string(&target).unwrap(),
+                format!("---\napplyTo: \"*.rs,style{}\"\n---\n\nRust.\n", suffix)
+            );
+
+            crate::parser::convert_github_to_cursor(&to_dir, &restored_dir, &options).unwrap();
+            let restored = fs::read_to_string(restored_dir.join("lang/rust.mdc")).unwrap();
+            assert!(restored.contains("globs:\n- '*.rs'\n- style.mdc\n"));
+            fs::remove_dir_all(&to_dir).unwrap();
+            fs::remove_dir_all(&restored_dir).unwrap();
+        }
+
+        let options = ConvertOptions {
+            target_extension: Some("md".to_string()),
+            ..Default::default()
+        };
+        let err = convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap_err();
+        assert!(err.to_string().contains("Invalid target extension `md`"));
+        assert!(!to_dir.exists());
+    }
+
+    #[test]
+    fn test_verify_complete_detects_missing_target() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        let to_dir = temp.path().join("instructions");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("good.mdc"),
+            "---\nglobs: \"*.rs\"\n---\n\nGood.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("bad.mdc"),
+            "---\ndescription: \"Malformed YAML\nglobs: [unclosed array\n---\nBody",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            verify_complete: true,
+            ..Default::default()
+        };
+        let err = convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("bad.mdc"));
+        assert!(message.contains("bad.instructions.md"));
+        assert!(!message.contains("good.mdc"));
+        assert!(to_dir.join("good.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_default_description_for_rule_without_description() {
+        let options = ConvertOptions {
+            default_description: Some("{name} rules ({path})".to_string()),
+            ..Default::default()
+        };
+        let input = "---\nglobs: \"*.py\"\n---\n\nBody.";
+        let output = convert_cursor_content_with_options(
+            input,
+            &options,
+            Some(Path::new("lang/python.mdc")),
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"python rules (lang/python.mdc)\"\napplyTo: \"*.py\"\n---\n\nBody."
+        );
+
+        // An existing description wins over the template
+        let input = "---\ndescription: \"Kept\"\nname: \"py\"\n---\n\nBody.";
+        let output = convert_cursor_content_with_options(input, &options, None).unwrap();
+        assert!(output.contains("description: \"Kept\"\n"));
+    }
+
+    #[test]
+    fn test_infer_description_from_first_heading() {
+        let options = ConvertOptions {
+            infer_description: true,
+            ..Default::default()
+        };
+        let input = "---\nglobs: \"*.rs\"\n---\n\n# Rust style\n\nBody.";
+        let output = convert_cursor_content_with_options(input, &options, None).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: \"Rust style\"\napplyTo: \"*.rs\"\n---\n\n# Rust style\n\nBody."
+        );
+
+        // An existing description wins over the heading
+        let input = "---\ndescription: \"Kept\"\n---\n\n# Heading\n\nBody.";
+        let output = convert_cursor_content_with_options(input, &options, None).unwrap();
+        assert!(output.contains("description: \"Kept\"\n"));
+    }
 }
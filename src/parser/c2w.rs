@@ -0,0 +1,170 @@
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+use super::batch::{run_batch, Converted};
+use super::common::{
+    find_cursor_files, parse_cursor_metadata, parse_frontmatter_with_field_info, quote_string,
+    restore_comments, with_source_body, write_target, WindsurfTrigger,
+};
+use super::defaults::read_cursor_rule;
+use super::lint::lint_cursor_rule;
+use super::options::{ConvertOptions, FrontmatterFormat};
+use crate::error::{Result, RulerError};
+
+pub fn convert_cursor_to_windsurf(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting Cursor rules to Windsurf rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    // Create target directory if it doesn't exist; check mode writes nothing
+    if !options.check {
+        fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+            path: to_dir.to_path_buf(),
+            source,
+        })?;
+    }
+
+    // Find all .mdc and .md files in the source directory
+    let source_files = find_cursor_files(from_dir, options)?;
+
+    if source_files.is_empty() && options.shows_progress() {
+        println!("No .mdc or .md files found in {}", from_dir.display());
+    }
+
+    run_batch(
+        from_dir,
+        to_dir,
+        Some(".md"),
+        source_files,
+        options,
+        // Change extension from .mdc to .md
+        |relative_path| relative_path.with_extension("md"),
+        |source_file, _relative_path, target_path| {
+            let content = read_cursor_rule(source_file, options)?;
+            let warnings = lint_cursor_rule(&content, options)?;
+            let output_content = convert_cursor_content_to_windsurf(&content, options)?;
+            let output_content = with_source_body(&content, output_content, options);
+            let written = write_target(target_path, &output_content, options)?;
+            Ok(Converted {
+                warnings,
+                ..Converted::target(target_path, written)
+            })
+        },
+    )
+}
+
+/// Converts the raw contents of a Cursor rule into Windsurf rule contents.
+///
+/// `alwaysApply: true` becomes `trigger: always_on`, non-empty `globs` become
+/// `trigger: glob`, a rule with only a description is left to the model
+/// (`trigger: model_decision`), and anything else is `trigger: manual`.
+pub fn convert_cursor_content_to_windsurf(
+    content: &str,
+    options: &ConvertOptions,
+) -> Result<String> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+
+    let fm = match frontmatter {
+        Some(fm) => fm,
+        None => {
+            debug!("no frontmatter detected");
+            return Ok(body);
+        }
+    };
+
+    debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
+    let cursor_meta = parse_cursor_metadata(&fm, field_info.format)?;
+    debug!("parsed {:?}", cursor_meta);
+
+    let globs = cursor_meta.globs.unwrap_or_default();
+    let description = cursor_meta.description.filter(|d| !d.is_empty());
+    let trigger = if cursor_meta.always_apply == Some(true) {
+        WindsurfTrigger::AlwaysOn
+    } else if !globs.is_empty() {
+        WindsurfTrigger::Glob
+    } else if description.is_some() {
+        WindsurfTrigger::ModelDecision
+    } else {
+        WindsurfTrigger::Manual
+    };
+    debug!(
+        "trigger: {} (alwaysApply: {:?}, globs: {:?}, description: {})",
+        trigger.as_str(),
+        cursor_meta.always_apply,
+        globs,
+        if description.is_some() {
+            "set"
+        } else {
+            "empty"
+        }
+    );
+
+    let format = options.frontmatter_format.unwrap_or(field_info.format);
+    let sep = match format {
+        FrontmatterFormat::Yaml => ":",
+        FrontmatterFormat::Toml => " =",
+    };
+    let mut out = String::new();
+    match format {
+        FrontmatterFormat::Yaml => out.push_str(&format!("trigger: {}\n", trigger.as_str())),
+        FrontmatterFormat::Toml => out.push_str(&format!("trigger = \"{}\"\n", trigger.as_str())),
+    }
+    if let Some(description) = &description {
+        out.push_str(&format!(
+            "description{} {}\n",
+            sep,
+            quote_string(description)
+        ));
+    }
+    if trigger == WindsurfTrigger::Glob {
+        out.push_str(&format!(
+            "globs{} {}\n",
+            sep,
+            quote_string(&globs.join(","))
+        ));
+    }
+
+    let out = restore_comments(&out, &field_info.comments, &[("alwaysApply", "trigger")]);
+    let delimiter = format.delimiter();
+    let header = field_info
+        .line_ending
+        .apply(&format!("{}\n{}{}\n\n", delimiter, out, delimiter));
+    Ok(format!("{}{}", header, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert_cursor_content_to_windsurf;
+    use crate::parser::options::ConvertOptions;
+
+    fn convert(input: &str) -> String {
+        convert_cursor_content_to_windsurf(input, &ConvertOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_globs_become_glob_trigger() {
+        let output =
+            convert("---\ndescription: \"TS\"\nglobs: [\"*.ts\", \"*.tsx\"]\n---\n\nBody.");
+        assert_eq!(
+            output,
+            "---\ntrigger: glob\ndescription: \"TS\"\nglobs: \"*.ts,*.tsx\"\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_activation_triggers() {
+        assert!(convert("---\nalwaysApply: true\n---\n\nBody.").contains("trigger: always_on\n"));
+        assert!(
+            convert("---\ndescription: \"When refactoring\"\n---\n\nBody.")
+                .contains("trigger: model_decision\n")
+        );
+        assert!(convert("---\nalwaysApply: false\n---\n\nBody.").contains("trigger: manual\n"));
+    }
+}
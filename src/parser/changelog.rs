@@ -0,0 +1,139 @@
+//! `--changelog-against`: what changed since a saved `--format json` report.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::batch::{ConversionStatus, JsonReport};
+use crate::error::{Result, RulerError};
+
+/// Targets that appeared, changed or disappeared between two runs, as
+/// `(target, source)` pairs sorted by target.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Changelog {
+    pub added: Vec<(String, String)>,
+    pub updated: Vec<(String, String)>,
+    pub removed: Vec<(String, String)>,
+}
+
+impl Changelog {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Reads a report saved with `--format json`, or a `--manifest` file.
+pub fn read_manifest(path: &Path) -> Result<JsonReport> {
+    let content = fs::read_to_string(path).map_err(|source| RulerError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&content).map_err(|source| RulerError::Manifest {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Compares the successful conversions of two reports by target path. A
+/// target present in both counts as updated when its digest changed; targets
+/// without a recorded digest are assumed unchanged.
+pub fn compute_changelog(previous: &JsonReport, current: &JsonReport) -> Changelog {
+    let successes = |report: &JsonReport| -> BTreeMap<String, (String, Option<String>)> {
+        report
+            .conversions
+            .iter()
+            .filter(|c| c.status == ConversionStatus::Success)
+            .map(|c| (c.target.clone(), (c.source.clone(), c.digest.clone())))
+            .collect()
+    };
+    let previous = successes(previous);
+    let current = successes(current);
+
+    let mut changelog = Changelog::default();
+    for (target, (source, digest)) in &current {
+        match previous.get(target) {
+            None => changelog.added.push((target.clone(), source.clone())),
+            Some((_, old_digest)) => {
+                if let (Some(old), Some(new)) = (old_digest, digest) {
+                    if old != new {
+                        changelog.updated.push((target.clone(), source.clone()));
+                    }
+                }
+            }
+        }
+    }
+    for (target, (source, _)) in &previous {
+        if !current.contains_key(target) {
+            changelog.removed.push((target.clone(), source.clone()));
+        }
+    }
+    changelog
+}
+
+/// Renders the changelog as Markdown grouped by kind of change, ready to
+/// paste into a pull request description.
+pub fn render_changelog(changelog: &Changelog) -> String {
+    let mut markdown = String::from("## Rule changes\n");
+    if changelog.is_empty() {
+        markdown.push_str("\nNo changes.\n");
+        return markdown;
+    }
+
+    let groups = [
+        ("Added", &changelog.added),
+        ("Updated", &changelog.updated),
+        ("Removed", &changelog.removed),
+    ];
+    for (heading, entries) in groups {
+        if entries.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("\n### {}\n\n", heading));
+        for (target, source) in entries {
+            markdown.push_str(&format!("- `{}` (from `{}`)\n", target, source));
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_changelog, render_changelog};
+    use crate::parser::batch::JsonReport;
+
+    fn report(json: serde_json::Value) -> JsonReport {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_changelog_lists_delta_between_manifests() {
+        let previous = report(serde_json::json!({
+            "conversions": [
+                { "source": "rules/a.mdc", "target": "out/a.instructions.md", "status": "success", "error": null, "digest": "1" },
+                { "source": "rules/b.mdc", "target": "out/b.instructions.md", "status": "success", "error": null, "digest": "2" },
+                { "source": "rules/old.mdc", "target": "out/old.instructions.md", "status": "success", "error": null, "digest": "3" }
+            ],
+            "success_count": 3,
+            "error_count": 0
+        }));
+        let current = report(serde_json::json!({
+            "conversions": [
+                { "source": "rules/a.mdc", "target": "out/a.instructions.md", "status": "success", "error": null, "digest": "1" },
+                { "source": "rules/b.mdc", "target": "out/b.instructions.md", "status": "success", "error": null, "digest": "20" },
+                { "source": "rules/new.mdc", "target": "out/new.instructions.md", "status": "success", "error": null, "digest": "4" },
+                { "source": "rules/broken.mdc", "target": "out/broken.instructions.md", "status": "error", "error": "parse error" }
+            ],
+            "success_count": 3,
+            "error_count": 1
+        }));
+
+        assert_eq!(
+            render_changelog(&compute_changelog(&previous, &current)),
+            "## Rule changes\n\n### Added\n\n- `out/new.instructions.md` (from `rules/new.mdc`)\n\n### Updated\n\n- `out/b.instructions.md` (from `rules/b.mdc`)\n\n### Removed\n\n- `out/old.instructions.md` (from `rules/old.mdc`)\n"
+        );
+        assert_eq!(
+            render_changelog(&compute_changelog(&current, &current)),
+            "## Rule changes\n\nNo changes.\n"
+        );
+    }
+}
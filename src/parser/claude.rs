@@ -0,0 +1,242 @@
+//! Conversion between Cursor rules and a `CLAUDE.md` project instructions file.
+//!
+//! `CLAUDE.md` is plain Markdown loaded into every session in the project, so
+//! it corresponds to Cursor's `alwaysApply: true` rules.
+
+use log::debug;
+use std::path::Path;
+
+use super::batch::{finish_batch, keeps_target, print_kept, print_target, FileOutcome};
+use super::color::{eprint_colored, Color};
+use super::common::{
+    first_heading, parse_cursor_metadata, parse_frontmatter_with_field_info, read_source_file,
+    serialize_frontmatter, write_target_with_parents, CursorMetadata,
+};
+use super::defaults::read_cursor_rule;
+use super::lint::lint_cursor_rule;
+use super::merge::{merge_into_file, RuleSection};
+use super::options::{ConvertOptions, FrontmatterFormat, OutputFormat};
+use crate::error::Result;
+
+/// Default path of the project instructions file.
+pub const CLAUDE_FILE: &str = "CLAUDE.md";
+
+/// File name of the rule `claude2c` writes.
+pub const CLAUDE_RULE: &str = "claude.mdc";
+
+/// Concatenates every `alwaysApply: true` rule in `from_dir` into `target`,
+/// one `## <title>` section per rule, ordered by descending `priority` and
+/// then by path. Rules scoped to globs or applied on request are left out.
+pub fn convert_cursor_to_claude(
+    from_dir: &Path,
+    target: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting always-applied Cursor rules to CLAUDE.md...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", target.display());
+    }
+
+    merge_into_file(from_dir, target, options, |source_file, relative| {
+        always_applied_section(source_file, relative, options)
+    })
+}
+
+/// The section `source_file` contributes to a project instructions file, or
+/// `None` when the rule isn't `alwaysApply: true`.
+pub(super) fn always_applied_section(
+    source_file: &Path,
+    relative: &Path,
+    options: &ConvertOptions,
+) -> Result<Option<(RuleSection, Vec<String>)>> {
+    let content = read_cursor_rule(source_file, options)?;
+    let warnings = lint_cursor_rule(&content, options)?;
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(&content)?;
+    let meta = match frontmatter {
+        Some(fm) => parse_cursor_metadata(&fm, field_info.format)?,
+        None => Default::default(),
+    };
+    if meta.always_apply != Some(true) {
+        debug!("{} is not always applied; skipped", source_file.display());
+        return Ok(None);
+    }
+    Ok(Some((
+        RuleSection::from_rule(&meta, body, relative),
+        warnings,
+    )))
+}
+
+/// Converts the `CLAUDE.md` file `source` into the always-applied Cursor rule
+/// `claude.mdc` in `to_dir`.
+pub fn convert_claude_to_cursor(
+    source: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting CLAUDE.md to a Cursor rule...");
+        println!("From: {}", source.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    convert_instructions_file(
+        source,
+        &to_dir.join(CLAUDE_RULE),
+        CLAUDE_FILE,
+        convert_claude_content,
+        options,
+    )
+}
+
+/// Converts the single project instructions file `source` with `convert` and
+/// writes the result to `target`, reporting it like a one-file batch.
+pub(super) fn convert_instructions_file(
+    source: &Path,
+    target: &Path,
+    default_name: &str,
+    convert: fn(&str) -> Result<String>,
+    options: &ConvertOptions,
+) -> Result<()> {
+    let text_output = options.format == OutputFormat::Text;
+    let kept = keeps_target(source, target, options);
+    // The source of a target `--overwrite` keeps isn't even read
+    let result = if kept {
+        Ok(false)
+    } else {
+        read_source_file(source, options)
+            .and_then(|content| convert(&content))
+            .and_then(|output| write_target_with_parents(target, &output, options))
+    };
+    let mut outcome = FileOutcome {
+        source: source.to_path_buf(),
+        relative: source
+            .file_name()
+            .map(Into::into)
+            .unwrap_or_else(|| default_name.into()),
+        targets: vec![target.to_path_buf()],
+        error: None,
+        warnings: Vec::new(),
+        not_utf8: false,
+        unchanged: Vec::new(),
+        kept,
+        dropped: Vec::new(),
+    };
+    match result {
+        Ok(_) if kept => {
+            if text_output && !options.check && !options.quiet {
+                print_kept(source, target, options);
+            }
+        }
+        Ok(written) => {
+            if !written {
+                outcome.unchanged.push(target.to_path_buf());
+            }
+            if text_output && !options.check && !options.quiet {
+                print_target(source, target, !written, options);
+            }
+        }
+        Err(e) => {
+            if text_output && options.check {
+                eprint_colored(options.color, Color::Red, &e.to_string());
+            } else if text_output {
+                let line = format!("Error converting {}: {}", source.display(), e);
+                eprint_colored(options.color, Color::Red, &line);
+            }
+            outcome.set_error(&e);
+        }
+    }
+
+    finish_batch(&[outcome], 0, options)
+}
+
+/// Converts the raw contents of a `CLAUDE.md` into an always-applied Cursor
+/// rule. The body is kept as is; its first `# ` heading, if any, becomes the
+/// description. Frontmatter in the source is dropped.
+pub fn convert_claude_content(content: &str) -> Result<String> {
+    let (_, body, _) = parse_frontmatter_with_field_info(content)?;
+    let description = first_heading(&body);
+
+    let meta = CursorMetadata {
+        description,
+        always_apply: Some(true),
+        ..Default::default()
+    };
+    let frontmatter = serialize_frontmatter(&meta, FrontmatterFormat::Yaml, "Cursor")?;
+    Ok(format!("---\n{}---\n\n{}", frontmatter, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_claude_content, convert_claude_to_cursor, convert_cursor_to_claude};
+    use crate::parser::options::{ConvertOptions, OverwritePolicy};
+    use std::fs;
+
+    #[test]
+    fn test_claude_md_becomes_an_always_applied_rule() {
+        let output =
+            convert_claude_content("# Project Guide\n\nRun `cargo test` before committing.\n")
+                .unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: Project Guide\nalwaysApply: true\n---\n\n# Project Guide\n\nRun `cargo test` before committing."
+        );
+
+        let output = convert_claude_content("Be brief.").unwrap();
+        assert_eq!(output, "---\nalwaysApply: true\n---\n\nBe brief.");
+    }
+
+    #[test]
+    fn test_only_always_applied_rules_go_into_claude_md() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("style.mdc"),
+            "---\ndescription: Style\nalwaysApply: true\n---\n\nBe concise.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("rust.mdc"),
+            "---\ndescription: Rust\nglobs: [\"*.rs\"]\n---\n\nUse clippy.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("security.mdc"),
+            "---\ndescription: Security\nalwaysApply: true\npriority: 1\n---\n\nNo secrets.",
+        )
+        .unwrap();
+
+        let target = temp.path().join("CLAUDE.md");
+        convert_cursor_to_claude(&from_dir, &target, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "## Security\n\nNo secrets.\n\n## Style\n\nBe concise.\n"
+        );
+    }
+
+    #[test]
+    fn test_overwrite_never_keeps_hand_written_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("style.mdc"),
+            "---\nalwaysApply: true\n---\n\nBe concise.",
+        )
+        .unwrap();
+        let claude = temp.path().join("CLAUDE.md");
+        fs::write(&claude, "Hand-written.").unwrap();
+        let rule = from_dir.join("claude.mdc");
+        fs::write(&rule, "Hand-written rule.").unwrap();
+
+        let options = ConvertOptions {
+            overwrite: OverwritePolicy::Never,
+            ..Default::default()
+        };
+        convert_cursor_to_claude(&from_dir, &claude, &options).unwrap();
+        assert_eq!(fs::read_to_string(&claude).unwrap(), "Hand-written.");
+        convert_claude_to_cursor(&claude, &from_dir, &options).unwrap();
+        assert_eq!(fs::read_to_string(&rule).unwrap(), "Hand-written rule.");
+    }
+}
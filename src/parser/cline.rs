@@ -0,0 +1,273 @@
+//! Conversion between Cursor rules and Cline rules (`.clinerules/*.md`).
+//!
+//! Cline applies every rule unless its frontmatter lists `paths`, in which
+//! case the rule is only active for matching files.
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::batch::{run_batch, Converted};
+use super::common::{
+    deserialize_description, deserialize_frontmatter, deserialize_globs, find_cursor_files,
+    find_markdown_files, parse_cursor_metadata, parse_frontmatter_with_field_info,
+    read_source_file, restore_comments, serialize_frontmatter, with_source_body, write_target,
+    CursorMetadata, FieldInfo,
+};
+use super::defaults::read_cursor_rule;
+use super::lint::lint_cursor_rule;
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClineMetadata {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "deserialize_description"
+    )]
+    pub description: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "deserialize_globs"
+    )]
+    pub paths: Option<Vec<String>>,
+}
+
+pub fn convert_cursor_to_cline(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting Cursor rules to Cline rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    // Create target directory if it doesn't exist; check mode writes nothing
+    if !options.check {
+        fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+            path: to_dir.to_path_buf(),
+            source,
+        })?;
+    }
+
+    // Find all .mdc and .md files in the source directory
+    let source_files = find_cursor_files(from_dir, options)?;
+
+    if source_files.is_empty() && options.shows_progress() {
+        println!("No .mdc or .md files found in {}", from_dir.display());
+    }
+
+    run_batch(
+        from_dir,
+        to_dir,
+        Some(".md"),
+        source_files,
+        options,
+        // Change extension from .mdc to .md
+        |relative_path| relative_path.with_extension("md"),
+        |source_file, _relative_path, target_path| {
+            let content = read_cursor_rule(source_file, options)?;
+            let warnings = lint_cursor_rule(&content, options)?;
+            let output_content = convert_cursor_content_to_cline(&content, options)?;
+            let output_content = with_source_body(&content, output_content, options);
+            let written = write_target(target_path, &output_content, options)?;
+            Ok(Converted {
+                warnings,
+                ..Converted::target(target_path, written)
+            })
+        },
+    )
+}
+
+pub fn convert_cline_to_cursor(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting Cline rules to Cursor rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    // Create target directory if it doesn't exist; check mode writes nothing
+    if !options.check {
+        fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+            path: to_dir.to_path_buf(),
+            source,
+        })?;
+    }
+
+    // Find all .md files in the source directory
+    let source_files = find_markdown_files(from_dir, options)?;
+
+    if source_files.is_empty() && options.shows_progress() {
+        println!("No .md files found in {}", from_dir.display());
+    }
+
+    run_batch(
+        from_dir,
+        to_dir,
+        Some(".mdc"),
+        source_files,
+        options,
+        // Change extension from .md to .mdc
+        |relative_path| relative_path.with_extension("mdc"),
+        |source_file, _relative_path, target_path| {
+            let content = read_source_file(source_file, options)?;
+            let output_content = convert_cline_content(&content, options)?;
+            let output_content = with_source_body(&content, output_content, options);
+            let written = write_target(target_path, &output_content, options)?;
+            Ok(Converted::target(target_path, written))
+        },
+    )
+}
+
+/// Converts the raw contents of a Cursor rule into Cline rule contents.
+///
+/// Globs become `paths` unless the rule is `alwaysApply: true`. Cline has no
+/// manual activation, so rules without globs are always active there.
+pub fn convert_cursor_content_to_cline(content: &str, options: &ConvertOptions) -> Result<String> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+
+    let fm = match frontmatter {
+        Some(fm) => fm,
+        None => {
+            debug!("no frontmatter detected");
+            return Ok(body);
+        }
+    };
+
+    debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
+    let cursor_meta = parse_cursor_metadata(&fm, field_info.format)?;
+    debug!("parsed {:?}", cursor_meta);
+
+    let paths = if cursor_meta.always_apply == Some(true) {
+        None
+    } else {
+        cursor_meta.globs.filter(|globs| !globs.is_empty())
+    };
+    debug!(
+        "alwaysApply: {:?} mapped to paths: {:?}",
+        cursor_meta.always_apply, paths
+    );
+    let cline_meta = ClineMetadata {
+        description: cursor_meta.description.filter(|d| !d.is_empty()),
+        paths,
+    };
+
+    if cline_meta.description.is_none() && cline_meta.paths.is_none() {
+        return Ok(body);
+    }
+    render(
+        &cline_meta,
+        "Cline",
+        body,
+        &field_info,
+        &[("globs", "paths"), ("alwaysApply", "paths")],
+        options,
+    )
+}
+
+/// Converts the raw contents of a Cline rule into Cursor rule contents.
+///
+/// `paths` become `globs` with `alwaysApply: false`; a rule without `paths`
+/// is `alwaysApply: true`.
+pub fn convert_cline_content(content: &str, options: &ConvertOptions) -> Result<String> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+
+    let cline_meta: ClineMetadata = match frontmatter {
+        Some(fm) => {
+            debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
+            deserialize_frontmatter(&fm, field_info.format, "Cline")?
+        }
+        None => {
+            debug!("no frontmatter detected");
+            ClineMetadata::default()
+        }
+    };
+    debug!("parsed {:?}", cline_meta);
+
+    let cursor_meta = match cline_meta.paths.filter(|paths| !paths.is_empty()) {
+        Some(paths) => CursorMetadata {
+            description: cline_meta.description,
+            globs: Some(paths),
+            always_apply: Some(false),
+            ..Default::default()
+        },
+        None => CursorMetadata {
+            description: cline_meta.description,
+            globs: Some(vec![]),
+            always_apply: Some(true),
+            ..Default::default()
+        },
+    };
+
+    render(
+        &cursor_meta,
+        "Cursor",
+        body,
+        &field_info,
+        &[("paths", "globs")],
+        options,
+    )
+}
+
+fn render<T: Serialize>(
+    meta: &T,
+    kind: &'static str,
+    body: String,
+    field_info: &FieldInfo,
+    comment_renames: &[(&str, &str)],
+    options: &ConvertOptions,
+) -> Result<String> {
+    let format = options.frontmatter_format.unwrap_or(field_info.format);
+    let frontmatter = restore_comments(
+        &serialize_frontmatter(meta, format, kind)?,
+        &field_info.comments,
+        comment_renames,
+    );
+    let delimiter = format.delimiter();
+    let header = field_info
+        .line_ending
+        .apply(&format!("{}\n{}{}\n\n", delimiter, frontmatter, delimiter));
+    Ok(format!("{}{}", header, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_cline_content, convert_cursor_content_to_cline};
+    use crate::parser::options::ConvertOptions;
+
+    #[test]
+    fn test_cursor_rule_survives_cline_round_trip() {
+        let options = ConvertOptions::default();
+        let input = "---\ndescription: \"React components\"\nglobs: [\"src/**/*.tsx\", \"src/**/*.jsx\"]\nalwaysApply: false\n---\n\nPrefer function components.";
+
+        let cline = convert_cursor_content_to_cline(input, &options).unwrap();
+        assert_eq!(
+            cline,
+            "---\ndescription: React components\npaths:\n- src/**/*.tsx\n- src/**/*.jsx\n---\n\nPrefer function components."
+        );
+
+        let cursor = convert_cline_content(&cline, &options).unwrap();
+        assert_eq!(
+            cursor,
+            "---\ndescription: React components\nglobs:\n- src/**/*.tsx\n- src/**/*.jsx\nalwaysApply: false\n---\n\nPrefer function components."
+        );
+    }
+
+    #[test]
+    fn test_cline_rule_without_paths_is_always_applied() {
+        let output =
+            convert_cline_content("Always follow the style guide.", &ConvertOptions::default())
+                .unwrap();
+        assert!(output.contains("alwaysApply: true\n"));
+        assert!(output.ends_with("Always follow the style guide."));
+    }
+}
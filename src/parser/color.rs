@@ -0,0 +1,70 @@
+//! `--color`: ANSI colors for the per-file progress, warning and error lines
+//! and the summary of a text-format run.
+
+use std::io::{self, IsTerminal};
+
+use super::options::ColorChoice;
+
+/// Color of a line of output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// Converted targets and clean summaries.
+    Green,
+    /// Warnings.
+    Yellow,
+    /// Errors and summaries of runs with errors.
+    Red,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Green => "32",
+            Color::Yellow => "33",
+            Color::Red => "31",
+        }
+    }
+}
+
+/// `text` wrapped in the escape codes for `color` when `enabled`, otherwise
+/// unchanged.
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Prints `line` to stdout, in `color` when `choice` allows it; `auto`
+/// colors only a terminal.
+pub fn print_colored(choice: ColorChoice, color: Color, line: &str) {
+    let enabled = match choice {
+        ColorChoice::Auto => io::stdout().is_terminal(),
+        choice => choice == ColorChoice::Always,
+    };
+    println!("{}", paint(line, color, enabled));
+}
+
+/// Like [`print_colored`], printing to stderr.
+pub fn eprint_colored(choice: ColorChoice, color: Color, line: &str) {
+    let enabled = match choice {
+        ColorChoice::Auto => io::stderr().is_terminal(),
+        choice => choice == ColorChoice::Always,
+    };
+    eprintln!("{}", paint(line, color, enabled));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paint, Color};
+
+    #[test]
+    fn test_paint_wraps_only_when_enabled() {
+        assert_eq!(
+            paint("Converted: a -> b", Color::Green, true),
+            "\x1b[32mConverted: a -> b\x1b[0m"
+        );
+        assert_eq!(paint("Warning: a", Color::Yellow, false), "Warning: a");
+    }
+}
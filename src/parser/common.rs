@@ -1,13 +1,29 @@
-use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::de::{self, DeserializeOwned};
 use serde::{Deserialize, Deserializer, Serialize};
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+use super::defaults::{is_defaults_rule, DEFAULTS_RULE};
+use super::env::expand_env;
+use super::include::expand_includes;
+use super::options::{ConvertOptions, FrontmatterFormat};
+use super::since::changed_since;
+use crate::error::{FrontmatterError, Result, RulerError};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CursorMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "deserialize_description"
+    )]
     pub description: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -23,19 +39,141 @@ pub struct CursorMetadata {
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    /// GitHub frontmatter keys Cursor has no field for, kept by g2c in a
+    /// `github:` block.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub github: Option<BTreeMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GithubMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "deserialize_description"
+    )]
     pub description: Option<String>,
-    #[serde(rename = "applyTo", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "applyTo",
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "deserialize_apply_to"
+    )]
     pub apply_to: Option<String>,
+    // Cursor-only fields carried through so a c2g/g2c round trip is lossless
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     #[serde(skip_deserializing)]
     pub description_present: bool,
     #[serde(skip_deserializing)]
     pub apply_to_present: bool,
+    /// The source wrote `description` as a `|` or `>` block scalar.
+    #[serde(skip_deserializing)]
+    pub description_block: bool,
+    /// Comments from the source frontmatter, written back on output.
+    #[serde(skip)]
+    pub comments: Vec<FrontmatterComment>,
+    /// Top-level keys of the source frontmatter in the order they appeared.
+    #[serde(skip)]
+    pub key_order: Vec<String>,
+    /// Keys none of the fields above model, such as `title` or an
+    /// organization's own fields.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Top-level keys [`GithubMetadata`] writes from its own fields, which a
+/// rule's `github:` block can't override.
+pub const GITHUB_FIELDS: [&str; 7] = [
+    "description",
+    "applyTo",
+    "name",
+    "authors",
+    "tags",
+    "version",
+    "priority",
+];
+
+/// Frontmatter of a Windsurf rule (`.windsurf/rules/*.md`).
+#[derive(Debug, Default, Deserialize)]
+pub struct WindsurfMetadata {
+    pub trigger: Option<WindsurfTrigger>,
+    #[serde(default, deserialize_with = "deserialize_description")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_globs")]
+    pub globs: Option<Vec<String>>,
+}
+
+/// When Windsurf activates a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindsurfTrigger {
+    /// Always included, like Cursor's `alwaysApply: true`
+    AlwaysOn,
+    /// Only when @-mentioned
+    Manual,
+    /// Picked by the model based on the rule's `description`
+    ModelDecision,
+    /// Included for files matching `globs`
+    Glob,
+}
+
+impl WindsurfTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WindsurfTrigger::AlwaysOn => "always_on",
+            WindsurfTrigger::Manual => "manual",
+            WindsurfTrigger::ModelDecision => "model_decision",
+            WindsurfTrigger::Glob => "glob",
+        }
+    }
+}
+
+// One element of a `globs` list. Some editor tooling writes entries as maps
+// (`- pattern: "*.ts"`) or nests lists inside the list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GlobEntry {
+    Pattern(String),
+    Keyed {
+        #[serde(alias = "glob")]
+        pattern: String,
+    },
+    Nested(Vec<GlobEntry>),
+    Unreadable(de::IgnoredAny),
+}
+
+impl GlobEntry {
+    fn collect_patterns(self, patterns: &mut Vec<String>) {
+        match self {
+            GlobEntry::Pattern(pattern) | GlobEntry::Keyed { pattern } => patterns.push(pattern),
+            GlobEntry::Nested(entries) => {
+                for entry in entries {
+                    entry.collect_patterns(patterns);
+                }
+            }
+            GlobEntry::Unreadable(_) => {}
+        }
+    }
+
+    fn count_unreadable(&self) -> usize {
+        match self {
+            GlobEntry::Nested(entries) => entries.iter().map(GlobEntry::count_unreadable).sum(),
+            GlobEntry::Unreadable(_) => 1,
+            _ => 0,
+        }
+    }
 }
 
 // Custom deserializer to handle multiple formats for globs:
@@ -43,11 +181,12 @@ pub struct GithubMetadata {
 // - Single string: "glob1"
 // - Comma-separated string: "glob1,glob2"
 // - Multiple quoted strings: "glob1", "glob2"
+// - Array entries that are maps with a `pattern`/`glob` key, or nested arrays
 pub fn deserialize_globs<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    use serde::de::{self, Visitor};
+    use serde::de::Visitor;
     use std::fmt;
 
     struct GlobsVisitor;
@@ -65,8 +204,8 @@ where
         {
             // Split by comma and trim whitespace, removing quotes if present
             if value.contains(',') {
-                let globs: Vec<String> = value
-                    .split(',')
+                let globs: Vec<String> = split_globs(value)
+                    .into_iter()
                     .map(|s| {
                         let trimmed = s.trim();
                         // Remove surrounding quotes if present
@@ -96,9 +235,11 @@ where
         where
             A: de::SeqAccess<'de>,
         {
+            // Entries that are neither strings nor pattern maps are skipped
+            // here; `lint_cursor_rule` warns about them
             let mut vec = Vec::new();
-            while let Some(value) = seq.next_element::<String>()? {
-                vec.push(value);
+            while let Some(entry) = seq.next_element::<GlobEntry>()? {
+                entry.collect_patterns(&mut vec);
             }
             Ok(Some(vec))
         }
@@ -121,43 +262,599 @@ where
     deserializer.deserialize_any(GlobsVisitor)
 }
 
-pub fn find_cursor_files(dir: &Path) -> Result<Vec<PathBuf>> {
+/// Roots tried, in order, when the default Cursor rules directory is absent.
+pub const ALTERNATE_RULE_ROOTS: &[&str] = &[".ai/rules", ".rules"];
+
+/// Picks the source directory when `--from` was not given: `primary` if it
+/// exists, else the first existing directory in `alternates`. Falls back to
+/// `primary` when none exist so the "does not exist" error names it.
+pub fn resolve_source_dir(primary: &Path, alternates: &[PathBuf]) -> PathBuf {
+    if primary.is_dir() {
+        return primary.to_path_buf();
+    }
+    alternates
+        .iter()
+        .find(|root| root.is_dir())
+        .cloned()
+        .unwrap_or_else(|| primary.to_path_buf())
+}
+
+/// Keeps the `files` under `dir` that match `--include` (when given), don't
+/// match `--exclude`, lie within `--max-depth` and, with `--since`, changed
+/// since that git ref. A Cursor rule also counts as changed when the
+/// `_defaults.mdc` it inherits from did.
+pub fn filter_sources(
+    dir: &Path,
+    files: Vec<PathBuf>,
+    options: &ConvertOptions,
+) -> Result<Vec<PathBuf>> {
+    if options.include.is_empty()
+        && options.exclude.is_empty()
+        && options.since.is_none()
+        && options.max_depth.is_none()
+    {
+        return Ok(files);
+    }
+    let include = build_glob_set(&options.include)?;
+    let exclude = build_glob_set(&options.exclude)?;
+    let changed = match &options.since {
+        Some(reference) => Some(changed_since(dir, reference)?),
+        None => None,
+    };
+
+    Ok(files
+        .into_iter()
+        .filter(|file| {
+            let relative = file.strip_prefix(dir).unwrap_or(file);
+            (options.include.is_empty() || include.is_match(relative))
+                && !exclude.is_match(relative)
+                && options
+                    .max_depth
+                    .is_none_or(|depth| relative.components().count() <= depth)
+                && changed.as_ref().is_none_or(|changed| {
+                    changed.contains(relative)
+                        || (is_cursor_file(file)
+                            && changed.contains(&relative.with_file_name(DEFAULTS_RULE)))
+                })
+        })
+        .collect())
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|source| RulerError::InvalidGlob {
+            glob: pattern.clone(),
+            source,
+        })?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+/// Fails with a clear message when the source directory `dir` is missing,
+/// rather than the walker's error for its first entry.
+pub fn check_source_dir(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        Ok(())
+    } else {
+        Err(RulerError::SourceDirMissing(dir.to_path_buf()))
+    }
+}
+
+// Walks `dir` for the files `is_source` accepts, skipping files matched by
+// `.gitignore`/`.ignore` rules unless `options.no_ignore` is set. Hidden files
+// are always visited. Symlinks are skipped unless `options.follow_symlinks` is
+// set; a symlinked directory that leads back to one of its ancestors is then
+// reported and not descended into again. Files come back sorted by path, so
+// the order doesn't depend on the file system.
+fn walk_sources(
+    dir: &Path,
+    options: &ConvertOptions,
+    is_source: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    check_source_dir(dir)?;
+    let respect_ignore = !options.no_ignore;
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .git_ignore(respect_ignore)
+        .git_global(respect_ignore)
+        .git_exclude(respect_ignore)
+        .ignore(respect_ignore)
+        .parents(respect_ignore)
+        .require_git(false)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth)
+        .build();
+
     let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) if is_symlink_loop(&e) => {
+                eprintln!("Warning: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        // Without `follow_links` a symlink's own file type is reported, so
+        // symlinked files are left out here
+        if entry.file_type().is_some_and(|t| t.is_file()) && is_source(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+
+    filter_sources(dir, files, options)
+}
+
+fn is_symlink_loop(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_symlink_loop(err),
+        _ => false,
+    }
+}
 
-    for entry in WalkDir::new(dir) {
-        let entry = entry.with_context(|| "Failed to read directory entry")?;
-        let path = entry.path();
+/// Finds the Cursor rules under `dir`, leaving out the `_defaults.mdc` rules
+/// that only supply frontmatter to their siblings.
+pub fn find_cursor_files(dir: &Path, options: &ConvertOptions) -> Result<Vec<PathBuf>> {
+    walk_sources(dir, options, |path| {
+        is_cursor_file(path) && !is_defaults_rule(path)
+    })
+}
 
-        if path.is_file() {
-            if let Some(ext) = path.extension() {
-                let ext_str = ext.to_string_lossy();
-                if ext_str.eq_ignore_ascii_case("mdc") || ext_str.eq_ignore_ascii_case("md") {
-                    files.push(path.to_path_buf());
-                }
+/// Whether `path` has a Cursor rule extension, `.mdc` or `.md`.
+pub fn is_cursor_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        let ext_str = ext.to_string_lossy();
+        ext_str.eq_ignore_ascii_case("mdc") || ext_str.eq_ignore_ascii_case("md")
+    })
+}
+
+pub fn find_github_files(dir: &Path, options: &ConvertOptions) -> Result<Vec<PathBuf>> {
+    walk_sources(dir, options, |path| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|file_name| {
+                file_name.ends_with(options.instructions_suffix()) || file_name.ends_with(".md")
+            })
+    })
+}
+
+/// The c2g target of the Cursor rule at `relative_path`: its `rename_map`
+/// entry, or else its stem with the instructions suffix, e.g. `style.mdc` ->
+/// `style.instructions.md`. A name that already ends with the suffix is kept
+/// rather than getting it twice.
+pub fn target_name_c2g(relative_path: &Path, options: &ConvertOptions) -> PathBuf {
+    if let Some(renamed) = options.renamed_target(relative_path) {
+        return renamed;
+    }
+    let suffix = options.instructions_suffix();
+    if relative_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|file_name| file_name.ends_with(suffix))
+    {
+        return relative_path.to_path_buf();
+    }
+    let file_stem = relative_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("file");
+    relative_path.with_file_name(format!("{}{}", file_stem, suffix))
+}
+
+/// The g2c target of the GitHub instruction at `relative_path`: its
+/// `rename_map` entry, or else its name with the instructions suffix, or
+/// failing that `.md`, replaced by `.mdc`, e.g. `style.instructions.md` ->
+/// `style.mdc`.
+pub fn target_name_g2c(relative_path: &Path, options: &ConvertOptions) -> PathBuf {
+    if let Some(renamed) = options.renamed_target(relative_path) {
+        return renamed;
+    }
+    let mut target_path = relative_path.to_path_buf();
+    if let Some(file_name) = target_path.file_name().and_then(|n| n.to_str()) {
+        if let Some(base_name) = file_name.strip_suffix(options.instructions_suffix()) {
+            target_path.set_file_name(format!("{}.mdc", base_name));
+        } else if let Some(base_name) = file_name.strip_suffix(".md") {
+            target_path.set_file_name(format!("{}.mdc", base_name));
+        } else {
+            // Fallback
+            target_path.set_extension("mdc");
+        }
+    }
+    target_path
+}
+
+// Visitor methods shared by the `description` and `applyTo` visitors: an
+// unquoted number or boolean is taken as the text it was written as
+macro_rules! visit_scalars_as_text {
+    () => {
+        visit_scalars_as_text!(visit_bool: bool, visit_i64: i64, visit_u64: u64, visit_f64: f64);
+    };
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            fn $method<E>(self, value: $ty) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Some(value.to_string()))
             }
+        )*
+    };
+}
+
+// Custom deserializer for descriptions authored either as a string or as a
+// list of bullet points, which are joined into one newline-separated string
+pub fn deserialize_description<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::{self, Visitor};
+    use std::fmt;
+
+    struct DescriptionVisitor;
+
+    impl<'de> Visitor<'de> for DescriptionVisitor {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a description string or a list of strings")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Some(value.to_string()))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut lines = Vec::new();
+            while let Some(line) = seq.next_element::<String>()? {
+                lines.push(line);
+            }
+            Ok(Some(lines.join("\n")))
+        }
+
+        visit_scalars_as_text!();
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
         }
     }
 
-    Ok(files)
+    deserializer.deserialize_any(DescriptionVisitor)
 }
 
-pub fn find_github_files(dir: &Path) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
+// Custom deserializer for `applyTo`, written either as a comma-separated
+// string or, like `globs`, as a list of patterns, which are joined with commas
+pub fn deserialize_apply_to<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::{self, Visitor};
+    use std::fmt;
 
-    for entry in WalkDir::new(dir) {
-        let entry = entry.with_context(|| "Failed to read directory entry")?;
-        let path = entry.path();
+    struct ApplyToVisitor;
 
-        if path.is_file() {
-            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                if file_name.ends_with(".instructions.md") || file_name.ends_with(".md") {
-                    files.push(path.to_path_buf());
-                }
+    impl<'de> Visitor<'de> for ApplyToVisitor {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a comma-separated string or a list of patterns")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Some(value.to_string()))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut patterns = Vec::new();
+            while let Some(entry) = seq.next_element::<GlobEntry>()? {
+                entry.collect_patterns(&mut patterns);
+            }
+            Ok(Some(patterns.join(",")))
+        }
+
+        visit_scalars_as_text!();
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(None)
+        }
+    }
+
+    deserializer.deserialize_any(ApplyToVisitor)
+}
+
+/// Finds the plain `.md` rules used by Windsurf and Cline.
+pub fn find_markdown_files(dir: &Path, options: &ConvertOptions) -> Result<Vec<PathBuf>> {
+    walk_sources(dir, options, |path| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+    })
+}
+
+/// Turns `text` into a lowercase, filename-safe slug made of ASCII letters,
+/// digits and single dashes, e.g. `src/**/*.ts` -> `src-ts`. Returns
+/// `fallback` when nothing is left.
+pub fn file_slug(text: &str, fallback: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// The text of the first `# ` heading in a Markdown `body`, if it isn't blank.
+/// Lines inside fenced code blocks, such as shell comments, aren't headings.
+pub fn first_heading(body: &str) -> Option<String> {
+    let mut in_fence = false;
+    body.lines()
+        .find_map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+            line.strip_prefix("# ").filter(|_| !in_fence)
+        })
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+/// Canonicalizes a tag list: trimmed, lowercased, deduplicated and sorted.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Splits a comma-separated glob list at the commas outside `{...}`
+/// alternatives, so `src/{a,b}/**,*.rs` is two patterns rather than three
+/// broken ones.
+pub fn split_globs(globs: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in globs.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&globs[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&globs[start..]);
+    parts
+}
+
+/// Cleans up a glob list before it is joined into, or after it is split from,
+/// a comma-separated `applyTo`: entries are split at commas (outside braces)
+/// and trimmed, and
+/// empty and repeated patterns are dropped, keeping the first-seen order.
+pub fn normalize_globs<I, S>(globs: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for entry in globs {
+        for glob in split_globs(entry.as_ref()).into_iter().map(str::trim) {
+            if !glob.is_empty() && !normalized.iter().any(|seen| seen == glob) {
+                normalized.push(glob.to_string());
             }
         }
     }
+    normalized
+}
+
+/// Reads a source file, applying the content preprocessing enabled in `options`.
+pub fn read_source_file(path: &Path, options: &ConvertOptions) -> Result<String> {
+    let bytes = fs::read(path).map_err(|source| RulerError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let content = String::from_utf8(bytes).map_err(|e| RulerError::NotUtf8 {
+        path: path.to_path_buf(),
+        offset: e.utf8_error().valid_up_to(),
+    })?;
+
+    let content = if options.expand_includes {
+        expand_includes(path, &content)?
+    } else {
+        content
+    };
+    if options.expand_env {
+        expand_env(&content, options.strict)
+    } else {
+        Ok(content)
+    }
+}
+
+/// `text` ending in exactly one line break, `\r\n` when it uses CRLF line
+/// endings. Empty text stays empty.
+pub fn with_trailing_newline(text: &str) -> String {
+    let trimmed = text.trim_end_matches(['\r', '\n']);
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    match LineEnding::detect(text) {
+        LineEnding::Lf => format!("{}\n", trimmed),
+        LineEnding::Crlf => format!("{}\r\n", trimmed),
+    }
+}
+
+/// Writes a converted target, first backing it up with `options.backup`, and
+/// returns whether it was written. `contents` is written with the `--eol`
+/// line endings and [`with_trailing_newline`]. A target that already has exactly those
+/// contents is left alone, keeping its modification time. In check mode
+/// nothing is written; instead the target must already exist with exactly
+/// those contents, and a unified diff of any difference is returned as the
+/// error.
+pub fn write_target(path: &Path, contents: &str, options: &ConvertOptions) -> Result<bool> {
+    // The body's trailing newlines are part of what `--frontmatter-only` keeps
+    let contents = if options.frontmatter_only {
+        options.eol.apply(contents)
+    } else {
+        with_trailing_newline(&options.eol.apply(contents))
+    };
+    let contents = contents.as_str();
+    if options.diff {
+        let existing = fs::read_to_string(path).ok();
+        let diff = render_target_diff(path, existing.as_deref(), contents);
+        print!("{}", diff);
+        return Ok(!diff.is_empty());
+    }
+    if !options.check {
+        let existing = fs::read(path).ok();
+        if existing.as_deref() == Some(contents.as_bytes()) {
+            return Ok(false);
+        }
+        if let Some(existing) = existing.filter(|_| options.backup) {
+            backup_target(path, existing)?;
+        }
+        fs::write(path, contents).map_err(|source| RulerError::Write {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        return Ok(true);
+    }
+
+    let existing = fs::read_to_string(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => RulerError::MissingTarget(path.to_path_buf()),
+        _ => RulerError::Read {
+            path: path.to_path_buf(),
+            source,
+        },
+    })?;
+    if existing != contents {
+        let diff = TextDiff::from_lines(existing.as_str(), contents)
+            .unified_diff()
+            .header(&path.display().to_string(), "expected")
+            .to_string();
+        return Err(RulerError::OutdatedTarget {
+            path: path.to_path_buf(),
+            diff,
+        });
+    }
+    Ok(false)
+}
+
+/// A unified diff from the `existing` contents of the target at `path` to
+/// `contents`, or an empty string when they're the same. A target that
+/// doesn't exist yet shows as entirely added.
+pub fn render_target_diff(path: &Path, existing: Option<&str>, contents: &str) -> String {
+    if existing == Some(contents) {
+        return String::new();
+    }
+    let path = path.display().to_string();
+    let old_header = if existing.is_some() {
+        path.as_str()
+    } else {
+        "/dev/null"
+    };
+    TextDiff::from_lines(existing.unwrap_or_default(), contents)
+        .unified_diff()
+        .header(old_header, &path)
+        .to_string()
+}
+
+/// Like [`write_target`], first creating the target's parent directories
+/// outside check mode.
+pub fn write_target_with_parents(
+    path: &Path,
+    contents: &str,
+    options: &ConvertOptions,
+) -> Result<bool> {
+    if let Some(parent) = path.parent().filter(|_| !options.check) {
+        fs::create_dir_all(parent).map_err(|source| RulerError::CreateDir {
+            path: parent.to_path_buf(),
+            source,
+        })?;
+    }
+    write_target(path, contents, options)
+}
+
+// Saves the `existing` contents of a target to `<target>.bak` before it is
+// overwritten with different contents. New or unchanged targets get no backup.
+fn backup_target(path: &Path, existing: Vec<u8>) -> Result<()> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    fs::write(&backup, existing).map_err(|source| RulerError::Write {
+        path: backup,
+        source,
+    })
+}
+
+/// Checks that every `(source, target)` pair produced a target on disk, listing
+/// any sources whose target is missing.
+pub fn verify_targets_exist(expected: &[(PathBuf, PathBuf)]) -> Result<()> {
+    let missing: Vec<(PathBuf, PathBuf)> = expected
+        .iter()
+        .filter(|(_, target)| !target.exists())
+        .cloned()
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
 
-    Ok(files)
+    Err(RulerError::Incomplete(missing))
+}
+
+/// Removes a leading UTF-8 byte-order mark, if any.
+pub fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
 }
 
 pub fn parse_frontmatter(content: &str) -> Result<(Option<String>, String)> {
@@ -166,75 +863,523 @@ pub fn parse_frontmatter(content: &str) -> Result<(Option<String>, String)> {
 }
 
 pub fn parse_frontmatter_with_field_info(content: &str) -> Result<(Option<String>, String, FieldInfo)> {
-    let content = content.trim();
+    // Editors on Windows may save a UTF-8 byte-order mark, which would hide the
+    // opening delimiter; it is dropped and never written back
+    let content = strip_bom(content).trim();
+    let line_ending = LineEnding::detect(content);
+    let no_frontmatter = || {
+        let field_info = FieldInfo {
+            line_ending,
+            ..Default::default()
+        };
+        Ok((None, content.to_string(), field_info))
+    };
+
+    let format = if content.starts_with("---") {
+        FrontmatterFormat::Yaml
+    } else if content.starts_with("+++") {
+        FrontmatterFormat::Toml
+    } else {
+        return no_frontmatter();
+    };
+    let delimiter = format.delimiter();
 
-    if !content.starts_with("---") {
-        return Ok((None, content.to_string(), FieldInfo::default()));
+    // Frontmatter needs at least three lines. The content is only split as
+    // far as that, so a large body isn't copied line by line
+    if content.splitn(3, '\n').nth(2).is_none() {
+        return no_frontmatter();
     }
 
-    let lines: Vec<&str> = content.lines().collect();
-    if lines.len() < 3 {
-        return Ok((None, content.to_string(), FieldInfo::default()));
+    match find_frontmatter_end(content, delimiter) {
+        Some((end, body_start)) => {
+            // Between the end of the opening delimiter's line and the closing one
+            let start = content.find('\n').map_or(end, |newline| newline + 1);
+            let frontmatter = content[start..end].lines().collect::<Vec<_>>().join("\n");
+            let body = content[body_start..].trim_start().to_string();
+
+            // Analyze which fields are present
+            let mut field_info = analyze_frontmatter_fields(&frontmatter);
+            field_info.line_ending = line_ending;
+            field_info.format = format;
+            field_info.comments = collect_comments(&frontmatter);
+
+            Ok((Some(frontmatter), body, field_info))
+        }
+        None => no_frontmatter(),
     }
+}
 
-    // Find the closing ---
-    let mut frontmatter_end = None;
-    for (i, line) in lines.iter().enumerate().skip(1) {
-        if line.trim() == "---" {
-            frontmatter_end = Some(i);
-            break;
+// Finds the closing delimiter, returning the byte offsets where its line
+// starts and where the body after it starts, so the frontmatter and the body
+// can be sliced out of `content`, the body with its original line endings
+// intact. Lines inside a `|` or `>` block scalar are text, even when they
+// read `---`
+fn find_frontmatter_end(content: &str, delimiter: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut block_indent = None;
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        if i == 0 {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if let Some(parent) = block_indent {
+            if line.trim().is_empty() || indent > parent {
+                continue;
+            }
+            block_indent = None;
+        }
+        if is_delimiter(line, delimiter) {
+            return Some((line_start, offset));
+        }
+        if opens_block_scalar(line) {
+            block_indent = Some(indent);
         }
     }
+    None
+}
 
-    match frontmatter_end {
-        Some(end) => {
-            let frontmatter = lines[1..end].join("\n");
-            let body = if end + 1 < lines.len() {
-                lines[end + 1..].join("\n").trim_start().to_string()
-            } else {
-                String::new()
-            };
+// The body of `content` exactly as written: everything after the line closing
+// its frontmatter, or all of it (bar a byte-order mark) when it has none.
+pub(super) fn raw_body(content: &str) -> &str {
+    let content = strip_bom(content);
+    let start = content.trim_start();
+    let format = if start.starts_with("---") {
+        FrontmatterFormat::Yaml
+    } else if start.starts_with("+++") {
+        FrontmatterFormat::Toml
+    } else {
+        return content;
+    };
+    match find_frontmatter_end(start, format.delimiter()) {
+        Some((_, body_start)) => &start[body_start..],
+        None => content,
+    }
+}
 
-            // Analyze which fields are present
-            let field_info = analyze_frontmatter_fields(&frontmatter);
+/// With `--frontmatter-only`, the frontmatter block of the `converted` output
+/// followed by the body of `source` byte for byte, in place of the trimmed body
+/// the conversion wrote; otherwise `converted` as is.
+pub fn with_source_body(source: &str, converted: String, options: &ConvertOptions) -> String {
+    if !options.frontmatter_only {
+        return converted;
+    }
+    let header_len = converted.len() - raw_body(&converted).len();
+    format!("{}{}", &converted[..header_len], raw_body(source))
+}
 
-            Ok((Some(frontmatter), body, field_info))
+// Whether `line` closes the frontmatter: the delimiter alone, ignoring
+// surrounding whitespace and a trailing `# comment`.
+fn is_delimiter(line: &str, delimiter: &str) -> bool {
+    match line.trim().strip_prefix(delimiter) {
+        Some(rest) => {
+            rest.is_empty() || (rest.starts_with([' ', '\t']) && rest.trim_start().starts_with('#'))
         }
-        None => Ok((None, content.to_string(), FieldInfo::default())),
+        None => false,
     }
 }
 
+// Whether a YAML line ends in a block scalar indicator such as `key: |`,
+// `key: >-` or `- |`, so the lines indented below it are text.
+fn opens_block_scalar(line: &str) -> bool {
+    let line = line.trim();
+    let Some((before, indicator)) = line.rsplit_once(' ') else {
+        return false;
+    };
+    (before.ends_with(':') || before.trim_start() == "-")
+        && indicator.starts_with(['|', '>'])
+        && indicator[1..]
+            .chars()
+            .all(|c| matches!(c, '-' | '+' | '1'..='9'))
+}
+
 #[derive(Debug, Default)]
 pub struct FieldInfo {
     pub description_present: bool,
+    /// `description` is a multi-line `|` or `>` block scalar.
+    pub description_block: bool,
     pub globs_present: bool,
+    /// A GitHub instruction's `applyTo` key is present, even with no value.
+    pub apply_to_present: bool,
+    pub line_ending: LineEnding,
+    pub format: FrontmatterFormat,
+    pub comments: Vec<FrontmatterComment>,
+    /// Top-level keys in the order they appear, used to keep that order on output.
+    pub key_order: Vec<String>,
+}
+
+/// A full-line `#` comment from a frontmatter block. YAML and TOML parsers
+/// drop comments, so they are captured separately and written back by
+/// [`restore_comments`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontmatterComment {
+    /// Top-level key of the field the comment precedes; `None` when it comes
+    /// after the last field.
+    pub key: Option<String>,
+    /// The comment as written, without leading indentation.
+    pub line: String,
+}
+
+// Returns the key of a top-level `key: value` / `key = value` line.
+pub(super) fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with([' ', '\t', '-', '#']) {
+        return None;
+    }
+    let end = line.find([':', '='])?;
+    Some(line[..end].trim().trim_matches(['"', '\'']))
+}
+
+fn collect_comments(frontmatter: &str) -> Vec<FrontmatterComment> {
+    let mut comments = Vec::new();
+    let mut pending = Vec::new();
+    let mut in_block_scalar = false;
+
+    for line in frontmatter.lines() {
+        let trimmed = line.trim_start();
+        // `#` lines indented under `key: |` or `key: >` are text, not comments
+        if in_block_scalar && trimmed.len() < line.len() {
+            continue;
+        }
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            pending.push(format!("#{}", comment));
+            continue;
+        }
+        if let Some(key) = top_level_key(line) {
+            let value = line[key.len()..].trim_start_matches([':', '=', ' ', '"', '\'']);
+            in_block_scalar = value.starts_with(['|', '>']);
+            comments.extend(pending.drain(..).map(|line| FrontmatterComment {
+                key: Some(key.to_string()),
+                line,
+            }));
+        }
+    }
+    comments.extend(
+        pending
+            .into_iter()
+            .map(|line| FrontmatterComment { key: None, line }),
+    );
+    comments
+}
+
+/// Writes `comments` back into a serialized frontmatter block, each before
+/// the field it preceded in the source. `renames` maps source keys to the
+/// output keys they became (e.g. `globs` to `applyTo`); comments whose field
+/// is not in the output go at the end.
+pub fn restore_comments(
+    frontmatter: &str,
+    comments: &[FrontmatterComment],
+    renames: &[(&str, &str)],
+) -> String {
+    if comments.is_empty() {
+        return frontmatter.to_string();
+    }
+    fn target_key<'a>(
+        comment: &'a FrontmatterComment,
+        renames: &[(&str, &'a str)],
+    ) -> Option<&'a str> {
+        let key = comment.key.as_deref()?;
+        Some(
+            renames
+                .iter()
+                .find(|(from, _)| *from == key)
+                .map_or(key, |(_, to)| *to),
+        )
+    }
+
+    let mut emitted = vec![false; comments.len()];
+    let mut out = String::new();
+    for line in frontmatter.lines() {
+        if let Some(key) = top_level_key(line) {
+            for (i, comment) in comments.iter().enumerate() {
+                if !emitted[i] && target_key(comment, renames) == Some(key) {
+                    out.push_str(&comment.line);
+                    out.push('\n');
+                    emitted[i] = true;
+                }
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    for (comment, _) in comments.iter().zip(emitted).filter(|(_, done)| !done) {
+        out.push_str(&comment.line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Reorders the fields of a serialized frontmatter block to follow
+/// `key_order`, the source's key order, so a conversion doesn't reshuffle
+/// keys. `renames` maps source keys to the output keys they became, as in
+/// [`restore_comments`]. A field with no source key stays right after the
+/// field it followed in `frontmatter`.
+pub fn reorder_fields(frontmatter: &str, key_order: &[String], renames: &[(&str, &str)]) -> String {
+    let source_position = |key: &str| {
+        key_order.iter().position(|source| {
+            source == key
+                || renames
+                    .iter()
+                    .any(|(from, to)| from == source && *to == key)
+        })
+    };
+
+    // Each field is its `key:` line plus any list items or block lines below it
+    let mut fields: Vec<(usize, String)> = Vec::new();
+    let mut position = 0;
+    for line in frontmatter.split_inclusive('\n') {
+        match top_level_key(line) {
+            Some(key) => {
+                position = source_position(key).unwrap_or(position);
+                fields.push((position, line.to_string()));
+            }
+            None => match fields.last_mut() {
+                Some((_, text)) => text.push_str(line),
+                None => fields.push((position, line.to_string())),
+            },
+        }
+    }
+
+    // Stable, so fields sharing a position keep their serialized order
+    fields.sort_by_key(|(position, _)| *position);
+    fields.into_iter().map(|(_, text)| text).collect()
+}
+
+/// Line-ending style of a source file, preserved in the converted output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn detect(content: &str) -> Self {
+        if content.contains("\r\n") {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Converts `\n`-terminated generated text to this line-ending style.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            LineEnding::Lf => text.to_string(),
+            LineEnding::Crlf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Renders `value` as a double-quoted scalar, valid in both YAML and TOML.
+/// Backslashes, quotes and control characters are escaped; other unicode is
+/// written as-is.
+pub fn quote_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Renders a multi-line `value` as a YAML literal block scalar (`|`), each
+/// line indented by two spaces, with the chomping indicator that keeps its
+/// trailing newlines exact. Returns `None` for single-line values and for
+/// values a plain `|` block can't hold, such as a first line starting with
+/// whitespace; those are written with [`quote_string`] instead.
+pub fn literal_block(value: &str) -> Option<String> {
+    let content = value.trim_end_matches('\n');
+    if !content.contains('\n')
+        || content.starts_with([' ', '\t', '\n'])
+        || content
+            .chars()
+            .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return None;
+    }
+
+    let trailing_newlines = value.len() - content.len();
+    let mut block = String::from(match trailing_newlines {
+        0 => "|-",
+        1 => "|",
+        _ => "|+",
+    });
+    for line in content.split('\n') {
+        block.push('\n');
+        if !line.is_empty() {
+            block.push_str("  ");
+            block.push_str(line);
+        }
+    }
+    for _ in 1..trailing_newlines {
+        block.push('\n');
+    }
+    Some(block)
+}
+
+/// Deserializes a frontmatter block returned by [`parse_frontmatter_with_field_info`]
+/// using the syntax its delimiter indicated. `kind` names the rule format in
+/// the error, e.g. `GitHub`.
+pub fn deserialize_frontmatter<T: DeserializeOwned>(
+    frontmatter: &str,
+    format: FrontmatterFormat,
+    kind: &'static str,
+) -> Result<T> {
+    let parsed = match format {
+        FrontmatterFormat::Yaml => {
+            serde_yaml::from_str(frontmatter).map_err(FrontmatterError::from)
+        }
+        FrontmatterFormat::Toml => toml::from_str(frontmatter).map_err(FrontmatterError::from),
+    };
+    parsed.map_err(|source| RulerError::Parse { kind, source })
+}
+
+/// Serializes metadata into a frontmatter block (without delimiters).
+pub fn serialize_frontmatter<T: Serialize>(
+    meta: &T,
+    format: FrontmatterFormat,
+    kind: &'static str,
+) -> Result<String> {
+    let serialized = match format {
+        FrontmatterFormat::Yaml => serde_yaml::to_string(meta).map_err(FrontmatterError::from),
+        FrontmatterFormat::Toml => toml::to_string(meta).map_err(FrontmatterError::from),
+    };
+    serialized.map_err(|source| RulerError::Serialize { kind, source })
+}
+
+/// Deserializes a Cursor rule's frontmatter, first normalizing the non-standard
+/// `globs` forms that YAML rules often use.
+pub fn parse_cursor_metadata(
+    frontmatter: &str,
+    format: FrontmatterFormat,
+) -> Result<CursorMetadata> {
+    deserialize_cursor_frontmatter(frontmatter, format)
+}
+
+/// Reads the Cursor rule at `rule` and parses its frontmatter, if it has any.
+pub fn read_cursor_metadata(rule: &Path) -> Result<Option<CursorMetadata>> {
+    let content = fs::read_to_string(rule).map_err(|source| RulerError::Read {
+        path: rule.to_path_buf(),
+        source,
+    })?;
+    let (frontmatter, _, field_info) = parse_frontmatter_with_field_info(&content)?;
+    frontmatter
+        .map(|fm| parse_cursor_metadata(&fm, field_info.format))
+        .transpose()
+}
+
+/// Counts the `globs` list entries that [`deserialize_globs`] had to skip
+/// because they are neither strings nor maps with a `pattern`/`glob` key.
+pub fn count_unreadable_globs(frontmatter: &str, format: FrontmatterFormat) -> Result<usize> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawGlobs {
+        List(Vec<GlobEntry>),
+        Other(de::IgnoredAny),
+    }
+
+    #[derive(Deserialize)]
+    struct RawMetadata {
+        globs: Option<RawGlobs>,
+    }
+
+    let raw: RawMetadata = deserialize_cursor_frontmatter(frontmatter, format)?;
+    Ok(match raw.globs {
+        Some(RawGlobs::List(entries)) => entries.iter().map(GlobEntry::count_unreadable).sum(),
+        _ => 0,
+    })
+}
+
+fn deserialize_cursor_frontmatter<T: DeserializeOwned>(
+    frontmatter: &str,
+    format: FrontmatterFormat,
+) -> Result<T> {
+    match format {
+        // Valid YAML, anchors and aliases included, is read as written; the
+        // line-based rewrite only repairs the `globs` forms YAML rejects
+        FrontmatterFormat::Yaml => {
+            deserialize_frontmatter(frontmatter, FrontmatterFormat::Yaml, "Cursor").or_else(|_| {
+                deserialize_frontmatter(
+                    &preprocess_frontmatter(frontmatter),
+                    FrontmatterFormat::Yaml,
+                    "Cursor",
+                )
+            })
+        }
+        FrontmatterFormat::Toml => {
+            deserialize_frontmatter(frontmatter, FrontmatterFormat::Toml, "Cursor")
+        }
+    }
 }
 
 fn analyze_frontmatter_fields(frontmatter: &str) -> FieldInfo {
     let mut info = FieldInfo::default();
 
+    // Only top-level keys count, not text inside a multi-line value
     for line in frontmatter.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("description:") {
+        let Some(key) = top_level_key(line) else {
+            continue;
+        };
+        info.key_order.push(key.to_string());
+        if key == "description" {
             info.description_present = true;
-        } else if trimmed.starts_with("globs:") {
+            let value = line[key.len()..].trim_start_matches([':', ' ', '"', '\'']);
+            info.description_block = value.starts_with(['|', '>']);
+        } else if key == "globs" {
             info.globs_present = true;
+        } else if key == "applyTo" {
+            info.apply_to_present = true;
         }
     }
 
     info
 }
 
+/// Byte offset of the colon separating a YAML key from its value: the first
+/// one outside quotes that is followed by whitespace or ends the line. Colons
+/// inside a value, as in `http://example/**`, are not separators.
+fn key_separator(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ':') if chars.peek().is_none_or(|&(_, next)| next.is_whitespace()) => {
+                return Some(i)
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 pub fn preprocess_frontmatter(frontmatter: &str) -> String {
     let mut result = String::new();
 
     for line in frontmatter.lines() {
-        if let Some(colon_pos) = line.find(':') {
+        if let Some(colon_pos) = key_separator(line) {
             let key = &line[..colon_pos];
             let value = &line[colon_pos + 1..].trim();
 
-            // Special handling for globs field with comma-separated strings
-            if key.trim() == "globs" && value.contains(',') && !value.starts_with('[') {
+            // Special handling for globs field with comma-separated strings.
+            // An anchored value (`&name ...`) is valid YAML and left alone, as
+            // is a comment opening a block list on the following lines
+            if key.trim().trim_matches(['"', '\'']) == "globs"
+                && value.contains(',')
+                && !value.starts_with(['[', '&', '#'])
+            {
                 // Handle two formats:
                 // 1. "string1", "string2" (multiple quoted strings)
                 // 2. "string1,string2,string3" (single quoted string with commas)
@@ -244,7 +1389,7 @@ pub fn preprocess_frontmatter(frontmatter: &str) -> String {
                 // Check if it's format 1: multiple quoted strings separated by commas
                 if value.contains("\", \"") || value.contains("', '") {
                     // Split on commas but preserve quoted strings
-                    for item in value.split(',') {
+                    for item in split_globs(value) {
                         let trimmed = item.trim();
                         if !trimmed.is_empty() {
                             array_items.push(trimmed.to_string());
@@ -261,7 +1406,7 @@ pub fn preprocess_frontmatter(frontmatter: &str) -> String {
                     };
 
                     // Split by comma and quote each item
-                    for item in unquoted.split(',') {
+                    for item in split_globs(unquoted) {
                         let trimmed = item.trim();
                         if !trimmed.is_empty() {
                             array_items.push(format!("\"{}\"", trimmed));
@@ -283,3 +1428,479 @@ pub fn preprocess_frontmatter(frontmatter: &str) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_cursor_files, find_github_files, first_heading, literal_block, parse_cursor_metadata,
+        parse_frontmatter_with_field_info, preprocess_frontmatter, raw_body, resolve_source_dir,
+        target_name_c2g, target_name_g2c, write_target, LineEnding,
+    };
+    use crate::error::RulerError;
+    use crate::parser::options::ConvertOptions;
+    use crate::parser::options::FrontmatterFormat;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_find_files_respects_gitignore() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join(".gitignore"), "node_modules/\n*.draft.md\n").unwrap();
+        fs::write(root.join("rule.mdc"), "Rule.").unwrap();
+        fs::write(root.join("nested/other.md"), "Other.").unwrap();
+        fs::write(root.join("notes.draft.md"), "Draft.").unwrap();
+        fs::write(root.join("node_modules/pkg/README.md"), "Vendored.").unwrap();
+
+        let mut files = find_cursor_files(root, &ConvertOptions::default()).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![root.join("nested/other.md"), root.join("rule.mdc")]
+        );
+
+        let files = find_github_files(root, &ConvertOptions::default()).unwrap();
+        assert_eq!(files, vec![root.join("nested/other.md")]);
+    }
+
+    #[test]
+    fn test_find_files_no_ignore_walks_everything() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(root.join("rule.mdc"), "Rule.").unwrap();
+        fs::write(root.join("node_modules/pkg/README.md"), "Vendored.").unwrap();
+
+        let options = ConvertOptions {
+            no_ignore: true,
+            ..Default::default()
+        };
+        let mut files = find_cursor_files(root, &options).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                root.join("node_modules/pkg/README.md"),
+                root.join("rule.mdc")
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_files_follows_symlinks_only_when_asked() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        let shared = temp.path().join("shared");
+        let root = temp.path().join("rules");
+        fs::create_dir_all(shared.join("lang")).unwrap();
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(shared.join("style.mdc"), "Shared.").unwrap();
+        fs::write(shared.join("lang/rust.mdc"), "Rust.").unwrap();
+        fs::write(root.join("rule.mdc"), "Rule.").unwrap();
+        symlink(shared.join("style.mdc"), root.join("style.mdc")).unwrap();
+        symlink(shared.join("lang"), root.join("lang")).unwrap();
+        // Leads back to `rules/`, which would loop forever if followed blindly
+        symlink(&root, root.join("nested/loop")).unwrap();
+
+        let files = find_cursor_files(&root, &ConvertOptions::default()).unwrap();
+        assert_eq!(files, vec![root.join("rule.mdc")]);
+
+        let options = ConvertOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let mut files = find_cursor_files(&root, &options).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                root.join("lang/rust.mdc"),
+                root.join("rule.mdc"),
+                root.join("style.mdc")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_files_applies_include_and_exclude() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("style.mdc"), "Style.").unwrap();
+        fs::write(root.join("style.draft.mdc"), "Draft.").unwrap();
+        fs::write(root.join("nested/api.mdc"), "API.").unwrap();
+        fs::write(root.join("nested/api.draft.mdc"), "Draft.").unwrap();
+
+        let options = ConvertOptions {
+            exclude: vec!["*.draft.mdc".to_string()],
+            ..Default::default()
+        };
+        let mut files = find_cursor_files(root, &options).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![root.join("nested/api.mdc"), root.join("style.mdc")]
+        );
+
+        let options = ConvertOptions {
+            include: vec!["nested/**".to_string()],
+            exclude: vec!["*.draft.mdc".to_string()],
+            ..Default::default()
+        };
+        let files = find_cursor_files(root, &options).unwrap();
+        assert_eq!(files, vec![root.join("nested/api.mdc")]);
+    }
+
+    #[test]
+    fn test_find_files_returns_paths_in_sorted_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        // Created out of order so directory order can't pass for sorting
+        for name in ["z.mdc", "b/y.mdc", "a.mdc", "b/a.mdc", "m.mdc"] {
+            let cursor = root.join("cursor").join(name);
+            let github = root
+                .join("github")
+                .join(name)
+                .with_extension("instructions.md");
+            for path in [&cursor, &github] {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, "Rule.").unwrap();
+            }
+        }
+
+        let expected = ["a", "b/a", "b/y", "m", "z"];
+        let options = ConvertOptions::default();
+        assert_eq!(
+            find_cursor_files(&root.join("cursor"), &options).unwrap(),
+            expected.map(|stem| root.join(format!("cursor/{}.mdc", stem)))
+        );
+        assert_eq!(
+            find_github_files(&root.join("github"), &options).unwrap(),
+            expected.map(|stem| root.join(format!("github/{}.instructions.md", stem)))
+        );
+    }
+
+    #[test]
+    fn test_find_files_stops_at_max_depth() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("lang/vendor/deep")).unwrap();
+        fs::write(root.join("style.mdc"), "Style.").unwrap();
+        fs::write(root.join("lang/rust.mdc"), "Rust.").unwrap();
+        fs::write(root.join("lang/vendor/deep/lib.mdc"), "Vendored.").unwrap();
+
+        let found = |max_depth| {
+            let options = ConvertOptions {
+                max_depth,
+                ..Default::default()
+            };
+            let mut files = find_cursor_files(root, &options).unwrap();
+            files.sort();
+            files
+        };
+        assert_eq!(found(None).len(), 3);
+        assert_eq!(
+            found(Some(2)),
+            vec![root.join("lang/rust.mdc"), root.join("style.mdc")]
+        );
+        assert_eq!(found(Some(1)), vec![root.join("style.mdc")]);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_keeps_crlf_body() {
+        let content = "---\r\ndescription: \"Windows\"\r\nglobs: \"*.cs\"\r\n---\r\n\r\n# Title\r\n\r\nLine one.\r\nLine two.";
+        let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content).unwrap();
+
+        assert_eq!(
+            frontmatter.unwrap(),
+            "description: \"Windows\"\nglobs: \"*.cs\""
+        );
+        assert_eq!(body, "# Title\r\n\r\nLine one.\r\nLine two.");
+        assert_eq!(field_info.line_ending, LineEnding::Crlf);
+        assert!(field_info.description_present);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_slices_a_large_body() {
+        let mut body = String::new();
+        for n in 0..100_000 {
+            body.push_str(&format!("Line {} of a generated rule.\r\n", n));
+            if n == 50_000 {
+                body.push_str("---\r\n");
+            }
+        }
+        let body = body.trim_end();
+        let content = format!(
+            "---\r\ndescription: \"Generated\"\r\n---\r\n\r\n{}\r\n",
+            body
+        );
+        let (frontmatter, parsed, field_info) =
+            parse_frontmatter_with_field_info(&content).unwrap();
+
+        assert_eq!(frontmatter.unwrap(), "description: \"Generated\"");
+        assert_eq!(parsed, body);
+        assert_eq!(raw_body(&content), format!("\r\n{}\r\n", body));
+        assert_eq!(field_info.line_ending, LineEnding::Crlf);
+
+        // Two delimiter lines and nothing else are not a frontmatter block
+        let (frontmatter, _, _) = parse_frontmatter_with_field_info("---\n---").unwrap();
+        assert!(frontmatter.is_none());
+    }
+
+    #[test]
+    fn test_field_info_tracks_empty_github_fields() {
+        let (_, _, field_info) =
+            parse_frontmatter_with_field_info("---\ndescription:\napplyTo:\n---\n\nBody.").unwrap();
+        assert!(field_info.description_present);
+        assert!(field_info.apply_to_present);
+        assert!(!field_info.globs_present);
+
+        let (_, _, field_info) =
+            parse_frontmatter_with_field_info("---\ndescription: x\n---\n\nBody.").unwrap();
+        assert!(!field_info.apply_to_present);
+    }
+
+    #[test]
+    fn test_dashes_inside_a_block_scalar_do_not_close_frontmatter() {
+        let content = "---\ndescription: |\n  Before the rule.\n  ---\n\n  After the rule.\nglobs: \"*.md\"\n--- # end\n\nBody.\n\n---\n\nMore body.";
+        let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content).unwrap();
+
+        assert_eq!(
+            frontmatter.unwrap(),
+            "description: |\n  Before the rule.\n  ---\n\n  After the rule.\nglobs: \"*.md\""
+        );
+        assert_eq!(body, "Body.\n\n---\n\nMore body.");
+        assert!(field_info.description_block);
+        assert!(field_info.globs_present);
+
+        // `----` is a horizontal rule, not a delimiter
+        let (frontmatter, _, _) =
+            parse_frontmatter_with_field_info("---\ndescription: x\n----\nBody.").unwrap();
+        assert!(frontmatter.is_none());
+    }
+
+    #[test]
+    fn test_literal_block_keeps_trailing_newlines() {
+        assert_eq!(literal_block("a\nb").unwrap(), "|-\n  a\n  b");
+        assert_eq!(literal_block("a\n\nb\n").unwrap(), "|\n  a\n\n  b");
+        assert_eq!(literal_block("a\nb\n\n").unwrap(), "|+\n  a\n  b\n");
+        for value in ["a\nb", "a\n\nb\n", "a\nb\n\n"] {
+            let yaml = format!("d: {}\n", literal_block(value).unwrap());
+            let parsed: std::collections::HashMap<String, String> =
+                serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(parsed["d"], value);
+        }
+        assert_eq!(literal_block("single line"), None);
+        assert_eq!(literal_block("  indented\nnext"), None);
+    }
+
+    #[test]
+    fn test_resolve_source_dir_falls_back_to_alternate_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let primary = temp.path().join(".cursor/rules");
+        let alternates = vec![temp.path().join(".ai/rules"), temp.path().join(".rules")];
+        fs::create_dir_all(&alternates[1]).unwrap();
+
+        assert_eq!(resolve_source_dir(&primary, &alternates), alternates[1]);
+
+        fs::create_dir_all(&primary).unwrap();
+        assert_eq!(resolve_source_dir(&primary, &alternates), primary);
+    }
+
+    #[test]
+    fn test_globs_list_with_mixed_entry_shapes() {
+        let frontmatter = "globs:\n  - \"*.ts\"\n  - pattern: \"src/**/*.py\"\n  - glob: \"*.rs\"\n    exclude: false\n  - [\"docs/**\", {pattern: \"*.md\"}]\n  - 42\n  - {exclude: true}\n";
+        let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+        assert_eq!(
+            meta.globs.unwrap(),
+            vec!["*.ts", "src/**/*.py", "*.rs", "docs/**", "*.md"]
+        );
+
+        let toml = "globs = [\"*.ts\", { glob = \"*.go\" }, 1]\n";
+        let meta = parse_cursor_metadata(toml, FrontmatterFormat::Toml).unwrap();
+        assert_eq!(meta.globs.unwrap(), vec!["*.ts", "*.go"]);
+    }
+
+    #[test]
+    fn test_anchored_globs_are_resolved() {
+        let frontmatter = "x-web: &web [\"*.ts\", \"*.tsx\"]\ndescription: Web\nglobs: *web\n";
+        let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+        assert_eq!(meta.globs.unwrap(), vec!["*.ts", "*.tsx"]);
+
+        let frontmatter = "globs: &shared [\"src/**\", \"lib/**\"]\nalwaysApply: false\n";
+        let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+        assert_eq!(meta.globs.unwrap(), vec!["src/**", "lib/**"]);
+
+        // Unquoted comma-separated globs still go through the rewrite
+        let meta = parse_cursor_metadata("globs: *.ts,*.tsx\n", FrontmatterFormat::Yaml).unwrap();
+        assert_eq!(meta.globs.unwrap(), vec!["*.ts", "*.tsx"]);
+    }
+
+    #[test]
+    fn test_target_names() {
+        let options = ConvertOptions::default();
+        for (source, c2g, g2c) in [
+            ("foo.mdc", "foo.instructions.md", "foo.mdc"),
+            ("foo.md", "foo.instructions.md", "foo.mdc"),
+            ("foo.instructions.md", "foo.instructions.md", "foo.mdc"),
+            (
+                "nested/foo.bar.mdc",
+                "nested/foo.bar.instructions.md",
+                "nested/foo.bar.mdc",
+            ),
+        ] {
+            assert_eq!(
+                target_name_c2g(Path::new(source), &options),
+                PathBuf::from(c2g)
+            );
+            assert_eq!(
+                target_name_g2c(Path::new(source), &options),
+                PathBuf::from(g2c)
+            );
+        }
+
+        let options = ConvertOptions {
+            target_extension: Some(".prompt.md".to_string()),
+            rename_map: [(PathBuf::from("a/old.mdc"), "new.md".to_string())].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            target_name_c2g(Path::new("foo.mdc"), &options),
+            PathBuf::from("foo.prompt.md")
+        );
+        assert_eq!(
+            target_name_g2c(Path::new("foo.prompt.md"), &options),
+            PathBuf::from("foo.mdc")
+        );
+        assert_eq!(
+            target_name_c2g(Path::new("a/old.mdc"), &options),
+            PathBuf::from("a/new.md")
+        );
+    }
+
+    #[test]
+    fn test_colons_in_glob_values_are_kept() {
+        for frontmatter in [
+            "globs: \"http://example/**,docs/**\"\n",
+            "globs: \"http://example/**\", \"docs/**\"\n",
+            "globs: http://example/**, docs/**\n",
+            "\"globs\": \"http://example/**,docs/**\"\n",
+        ] {
+            assert_eq!(
+                preprocess_frontmatter(frontmatter).trim_end(),
+                frontmatter
+                    .split_once(':')
+                    .map(|(key, _)| format!("{}: [\"http://example/**\", \"docs/**\"]", key))
+                    .unwrap(),
+            );
+            let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+            assert_eq!(
+                meta.globs.unwrap(),
+                vec!["http://example/**", "docs/**"],
+                "{}",
+                frontmatter
+            );
+        }
+
+        let meta = parse_cursor_metadata("globs: \"http://example/**\"\n", FrontmatterFormat::Yaml)
+            .unwrap();
+        assert_eq!(meta.globs.unwrap(), vec!["http://example/**"]);
+    }
+
+    #[test]
+    fn test_braced_globs_stay_whole() {
+        for frontmatter in [
+            "globs: \"src/{a,b}/**\"\n",
+            "globs: src/{a,b}/**\n",
+            "globs: [\"src/{a,b}/**\"]\n",
+        ] {
+            let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+            assert_eq!(meta.globs.unwrap(), vec!["src/{a,b}/**"], "{}", frontmatter);
+        }
+
+        for frontmatter in [
+            "globs: \"src/{a,b}/**,*.{ts,tsx}\"\n",
+            "globs: \"src/{a,b}/**\", \"*.{ts,tsx}\"\n",
+            "globs: src/{a,b}/**, *.{ts,tsx}\n",
+        ] {
+            let meta = parse_cursor_metadata(frontmatter, FrontmatterFormat::Yaml).unwrap();
+            assert_eq!(
+                meta.globs.unwrap(),
+                vec!["src/{a,b}/**", "*.{ts,tsx}"],
+                "{}",
+                frontmatter
+            );
+        }
+    }
+
+    #[test]
+    fn test_identical_target_is_not_rewritten() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("style.instructions.md");
+        let options = ConvertOptions::default();
+
+        assert!(write_target(&target, "Generated.", &options).unwrap());
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&target)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        assert!(!write_target(&target, "Generated.", &options).unwrap());
+        assert_eq!(fs::metadata(&target).unwrap().modified().unwrap(), old);
+
+        assert!(write_target(&target, "Regenerated.", &options).unwrap());
+        assert_ne!(fs::metadata(&target).unwrap().modified().unwrap(), old);
+    }
+
+    #[test]
+    fn test_backup_only_when_overwriting_different_contents() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("style.instructions.md");
+        let backup = temp.path().join("style.instructions.md.bak");
+        let options = ConvertOptions {
+            backup: true,
+            ..Default::default()
+        };
+
+        write_target(&target, "Generated.", &options).unwrap();
+        assert!(!backup.exists());
+        write_target(&target, "Generated.", &options).unwrap();
+        assert!(!backup.exists());
+
+        fs::write(&target, "Hand-edited.").unwrap();
+        write_target(&target, "Generated.", &options).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "Generated.\n");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "Hand-edited.");
+    }
+
+    #[test]
+    fn test_check_reports_unreadable_targets_as_read_errors() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("style.instructions.md");
+        let options = ConvertOptions {
+            check: true,
+            ..Default::default()
+        };
+
+        let error = write_target(&target, "Generated.", &options).unwrap_err();
+        assert!(matches!(error, RulerError::MissingTarget(_)));
+        fs::write(&target, b"Caf\xe9").unwrap();
+        let error = write_target(&target, "Generated.", &options).unwrap_err();
+        assert!(matches!(error, RulerError::Read { .. }));
+    }
+
+    #[test]
+    fn test_first_heading_skips_fenced_code() {
+        let body = "Setup:\n\n```sh\n# install the tools\nmake setup\n```\n\n# Build\n\nRun make.";
+        assert_eq!(first_heading(body), Some("Build".to_string()));
+        assert_eq!(first_heading("~~~\n# comment\n~~~\n"), None);
+    }
+}
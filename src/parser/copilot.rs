@@ -0,0 +1,348 @@
+//! The single repo-wide `.github/copilot-instructions.md` file: `c2g-merge`
+//! combines every Cursor rule into it and `g2c-split` splits it back.
+//!
+//! Copilot applies the whole file to every request, so it cannot express a
+//! rule's globs. Each section therefore starts with a `<!-- ruler: {...} -->`
+//! marker recording the rule's path and metadata, which `g2c-split` uses to
+//! restore the original rules.
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use super::batch::write_split_targets;
+use super::common::{
+    file_slug, parse_cursor_metadata, parse_frontmatter_with_field_info, read_source_file,
+    serialize_frontmatter, CursorMetadata,
+};
+use super::defaults::read_cursor_rule;
+use super::lint::lint_cursor_rule;
+use super::merge::{merge_into_file, RuleSection};
+use super::options::{ConvertOptions, FrontmatterFormat};
+use crate::error::Result;
+
+/// Default path of the merged instructions file.
+pub const COPILOT_INSTRUCTIONS: &str = ".github/copilot-instructions.md";
+
+const MARKER_PREFIX: &str = "<!-- ruler: ";
+const MARKER_SUFFIX: &str = " -->";
+
+/// What a section marker records about the rule it came from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectionMarker {
+    /// Path of the rule relative to the rules directory.
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub globs: Vec<String>,
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub always_apply: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub priority: Option<i64>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Merges every Cursor rule in `from_dir` into the single instructions file
+/// `target`, one `## <title>` section per rule. Sections are ordered by
+/// descending `priority`, then by rule path.
+pub fn merge_cursor_rules(from_dir: &Path, target: &Path, options: &ConvertOptions) -> Result<()> {
+    if options.shows_progress() {
+        println!("Merging Cursor rules into GitHub Copilot instructions...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", target.display());
+    }
+
+    merge_into_file(from_dir, target, options, |source_file, relative| {
+        read_rule_section(source_file, relative, options).map(Some)
+    })
+}
+
+// Reads one rule as a merged section whose body starts with its marker.
+fn read_rule_section(
+    source_file: &Path,
+    relative: &Path,
+    options: &ConvertOptions,
+) -> Result<(RuleSection, Vec<String>)> {
+    let content = read_cursor_rule(source_file, options)?;
+    let warnings = lint_cursor_rule(&content, options)?;
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(&content)?;
+    let meta = match frontmatter {
+        Some(fm) => parse_cursor_metadata(&fm, field_info.format)?,
+        None => Default::default(),
+    };
+
+    let always_apply = meta.always_apply == Some(true);
+    let marker = SectionMarker {
+        path: relative.to_path_buf(),
+        description: meta.description.clone().filter(|d| !d.is_empty()),
+        globs: if always_apply {
+            Vec::new()
+        } else {
+            meta.globs.clone().unwrap_or_default()
+        },
+        always_apply,
+        priority: meta.priority,
+    };
+    let marker = format!(
+        "{}{}{}",
+        MARKER_PREFIX,
+        marker_json(&marker)?,
+        MARKER_SUFFIX
+    );
+    let body = format!("{}\n\n{}", marker, body.trim());
+    Ok((RuleSection::from_rule(&meta, body, relative), warnings))
+}
+
+/// Splits a merged instructions file back into Cursor rules under `to_dir`.
+pub fn split_copilot_instructions(
+    source: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Splitting GitHub Copilot instructions into Cursor rules...");
+        println!("From: {}", source.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    let content = read_source_file(source, options)?;
+    let rules = split_copilot_content(&content)?;
+
+    write_split_targets(source, to_dir, rules, options)
+}
+
+/// Splits the contents of a merged instructions file into `(path, contents)`
+/// pairs, one Cursor rule per section.
+///
+/// Sections written by `c2g-merge` are restored from their markers. In a file
+/// without markers every `## ` heading outside a code block starts a section,
+/// which becomes an `alwaysApply: true` rule described by its heading and named
+/// after it; text before the first heading becomes `copilot-instructions.mdc`.
+pub fn split_copilot_content(content: &str) -> Result<Vec<(PathBuf, String)>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let has_markers = lines.iter().any(|line| parse_marker(line).is_some());
+
+    // (heading line, marker, first body line) of every section
+    let mut starts: Vec<(usize, Option<SectionMarker>, usize)> = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence || !line.starts_with("## ") {
+            continue;
+        }
+        if !has_markers {
+            starts.push((i, None, i + 1));
+            continue;
+        }
+        let next = (i + 1..lines.len()).find(|&j| !lines[j].trim().is_empty());
+        if let Some((j, marker)) = next.and_then(|j| parse_marker(lines[j]).map(|m| (j, m))) {
+            starts.push((i, Some(marker), j + 1));
+        }
+    }
+    debug!("found {} section(s)", starts.len());
+
+    let mut rules = Vec::new();
+    let preamble = lines[..starts.first().map_or(lines.len(), |s| s.0)].join("\n");
+    if !preamble.trim().is_empty() {
+        let meta = SectionMarker {
+            path: PathBuf::from("copilot-instructions.mdc"),
+            always_apply: true,
+            ..Default::default()
+        };
+        rules.push((meta.path.clone(), render_rule(&meta, &preamble)?));
+    }
+
+    let mut used_paths = HashSet::new();
+    for (index, (heading, marker, body_start)) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).map_or(lines.len(), |next| next.0);
+        let body = lines[*body_start..end].join("\n");
+        let title = lines[*heading].trim_start_matches('#').trim();
+        let heading_path = || PathBuf::from(format!("{}.mdc", file_slug(title, "section")));
+        let marker = match marker {
+            Some(marker) if stays_inside(&marker.path) => marker.clone(),
+            // A path that would leave the target directory is not trusted
+            Some(marker) => SectionMarker {
+                path: heading_path(),
+                ..marker.clone()
+            },
+            None => SectionMarker {
+                path: heading_path(),
+                description: Some(title.to_string()).filter(|t| !t.is_empty()),
+                always_apply: true,
+                ..Default::default()
+            },
+        };
+
+        // Two headings with the same slug would overwrite each other
+        let mut path = marker.path.clone();
+        let mut suffix = 2;
+        while !used_paths.insert(path.clone()) {
+            let stem = marker
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            path = marker
+                .path
+                .with_file_name(format!("{}-{}.mdc", stem, suffix));
+            suffix += 1;
+        }
+        rules.push((path, render_rule(&marker, &body)?));
+    }
+    Ok(rules)
+}
+
+// Whether `path` names a file below the directory it is joined to: relative,
+// without `..` or root components.
+fn stays_inside(path: &Path) -> bool {
+    path.file_name().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+// The marker's JSON with `>` and the second `-` of `--` escaped, so text such
+// as `-->` in a description can't end the comment early.
+fn marker_json(marker: &SectionMarker) -> Result<String> {
+    Ok(serde_json::to_string(marker)?
+        .replace('>', "\\u003e")
+        .replace("--", "-\\u002d"))
+}
+
+fn parse_marker(line: &str) -> Option<SectionMarker> {
+    let json = line
+        .trim()
+        .strip_prefix(MARKER_PREFIX)?
+        .strip_suffix(MARKER_SUFFIX)?;
+    serde_json::from_str(json).ok()
+}
+
+fn render_rule(marker: &SectionMarker, body: &str) -> Result<String> {
+    let meta = CursorMetadata {
+        description: marker.description.clone(),
+        globs: Some(marker.globs.clone()).filter(|globs| !globs.is_empty()),
+        always_apply: Some(marker.always_apply),
+        priority: marker.priority,
+        ..Default::default()
+    };
+    let frontmatter = serialize_frontmatter(&meta, FrontmatterFormat::Yaml, "Cursor")?;
+    Ok(format!("---\n{}---\n\n{}\n", frontmatter, body.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_cursor_rules, split_copilot_content, split_copilot_instructions};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_merge_orders_sections_by_priority_then_path() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(from_dir.join("lang")).unwrap();
+        // Written out of order so the walk order can't be relied on
+        fs::write(
+            from_dir.join("lang/rust.mdc"),
+            "---\ndescription: Rust\nglobs: [\"*.rs\"]\n---\n\nUse clippy.",
+        )
+        .unwrap();
+        fs::write(from_dir.join("zeta.mdc"), "---\nname: zeta\n---\n\nZeta.").unwrap();
+        fs::write(
+            from_dir.join("security.mdc"),
+            "---\ndescription: Security\npriority: 5\nalwaysApply: true\n---\n\nNo secrets.",
+        )
+        .unwrap();
+        fs::write(from_dir.join("alpha.mdc"), "Alpha.").unwrap();
+
+        let target = temp.path().join(".github/copilot-instructions.md");
+        merge_cursor_rules(&from_dir, &target, &ConvertOptions::default()).unwrap();
+        let merged = fs::read_to_string(&target).unwrap();
+        assert_eq!(
+            merged,
+            "## Security\n\n<!-- ruler: {\"path\":\"security.mdc\",\"description\":\"Security\",\"always_apply\":true,\"priority\":5} -->\n\nNo secrets.\n\
+             \n## alpha\n\n<!-- ruler: {\"path\":\"alpha.mdc\"} -->\n\nAlpha.\n\
+             \n## Rust\n\n<!-- ruler: {\"path\":\"lang/rust.mdc\",\"description\":\"Rust\",\"globs\":[\"*.rs\"]} -->\n\nUse clippy.\n\
+             \n## zeta\n\n<!-- ruler: {\"path\":\"zeta.mdc\"} -->\n\nZeta.\n"
+        );
+
+        // Merging again gives the same file
+        merge_cursor_rules(&from_dir, &target, &ConvertOptions::default()).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), merged);
+
+        let restored = temp.path().join("restored");
+        split_copilot_instructions(&target, &restored, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(restored.join("lang/rust.mdc")).unwrap(),
+            "---\ndescription: Rust\nglobs:\n- '*.rs'\nalwaysApply: false\n---\n\nUse clippy.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(restored.join("security.mdc")).unwrap(),
+            "---\ndescription: Security\nalwaysApply: true\npriority: 5\n---\n\nNo secrets.\n"
+        );
+    }
+
+    #[test]
+    fn test_split_without_markers_uses_headings() {
+        let content = "Be kind.\n\n## Code Style\n\nUse tabs.\n\n```md\n## Not a section\n```\n\n## Testing\n\nWrite tests.\n\n## Code style\n\nAgain.\n";
+        let rules = split_copilot_content(content).unwrap();
+        let paths: Vec<PathBuf> = rules.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("copilot-instructions.mdc"),
+                PathBuf::from("code-style.mdc"),
+                PathBuf::from("testing.mdc"),
+                PathBuf::from("code-style-2.mdc"),
+            ]
+        );
+        assert_eq!(
+            rules[1].1,
+            "---\ndescription: Code Style\nalwaysApply: true\n---\n\nUse tabs.\n\n```md\n## Not a section\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_markers_cannot_escape_the_target_directory() {
+        let content = "## Escaped\n\n<!-- ruler: {\"path\":\"../../escaped.mdc\",\"description\":\"Kept\"} -->\n\nOut.\n\n## Absolute\n\n<!-- ruler: {\"path\":\"/tmp/absolute.mdc\"} -->\n\nRoot.\n\n## Nested\n\n<!-- ruler: {\"path\":\"lang/rust.mdc\"} -->\n\nIn.\n";
+        let rules = split_copilot_content(content).unwrap();
+        let paths: Vec<PathBuf> = rules.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("escaped.mdc"),
+                PathBuf::from("absolute.mdc"),
+                PathBuf::from("lang/rust.mdc"),
+            ]
+        );
+        assert!(rules[0].1.contains("description: Kept\n"));
+    }
+
+    #[test]
+    fn test_marker_escapes_comment_end() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("arrows.mdc"),
+            "---\ndescription: \"a --> b\"\n---\n\nBody.",
+        )
+        .unwrap();
+
+        let target = temp.path().join("copilot-instructions.md");
+        merge_cursor_rules(&from_dir, &target, &ConvertOptions::default()).unwrap();
+        let merged = fs::read_to_string(&target).unwrap();
+        assert!(merged.contains("\"description\":\"a -\\u002d\\u003e b\"} -->\n"));
+        let rules = split_copilot_content(&merged).unwrap();
+        assert_eq!(rules[0].0, PathBuf::from("arrows.mdc"));
+        assert!(rules[0].1.contains("description: a --> b\n"));
+    }
+}
@@ -0,0 +1,196 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::common::{find_cursor_files, parse_cursor_metadata, parse_frontmatter_with_field_info};
+use super::defaults::read_cursor_rule;
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+/// Which project files are matched by at least one rule.
+#[derive(Debug, Default, Serialize)]
+pub struct CoverageReport {
+    /// Files matched by a rule's globs (or covered by an `alwaysApply` rule),
+    /// relative to the project root.
+    pub covered: Vec<PathBuf>,
+    /// Files no rule applies to, relative to the project root.
+    pub uncovered: Vec<PathBuf>,
+}
+
+impl CoverageReport {
+    /// Share of project files covered, as a percentage (100 for an empty project).
+    pub fn percent(&self) -> f64 {
+        let total = self.covered.len() + self.uncovered.len();
+        if total == 0 {
+            100.0
+        } else {
+            self.covered.len() as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Matches the globs of every Cursor rule in `rules_dir` against the files
+/// under `project_root`. Like discovery, the walk skips `.gitignore`d files
+/// unless `options.no_ignore` is set; hidden files and directories are skipped.
+pub fn compute_coverage(
+    rules_dir: &Path,
+    project_root: &Path,
+    options: &ConvertOptions,
+) -> Result<CoverageReport> {
+    let (globs, always_applied) = collect_rule_globs(rules_dir, options)?;
+
+    let mut report = CoverageReport::default();
+    let respect_ignore = !options.no_ignore;
+    let walker = WalkBuilder::new(project_root)
+        .git_ignore(respect_ignore)
+        .git_global(respect_ignore)
+        .git_exclude(respect_ignore)
+        .ignore(respect_ignore)
+        .parents(respect_ignore)
+        .require_git(false)
+        .build();
+
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(project_root)
+            .unwrap_or(entry.path())
+            .to_path_buf();
+
+        if always_applied || globs.is_match(&relative) {
+            report.covered.push(relative);
+        } else {
+            report.uncovered.push(relative);
+        }
+    }
+
+    report.covered.sort();
+    report.uncovered.sort();
+    Ok(report)
+}
+
+// Builds one matcher over every rule's globs. The second value is true when
+// some rule is `alwaysApply: true` and so covers every file.
+fn collect_rule_globs(rules_dir: &Path, options: &ConvertOptions) -> Result<(GlobSet, bool)> {
+    let mut builder = GlobSetBuilder::new();
+    let mut always_applied = false;
+
+    for rule in find_cursor_files(rules_dir, options)? {
+        let meta = read_cursor_rule(&rule, options).and_then(|content| {
+            let (frontmatter, _, field_info) = parse_frontmatter_with_field_info(&content)?;
+            frontmatter
+                .map(|fm| parse_cursor_metadata(&fm, field_info.format))
+                .transpose()
+        });
+        let meta = match meta {
+            Ok(Some(meta)) => meta,
+            Ok(None) => continue,
+            Err(e) => {
+                return Err(RulerError::Rule {
+                    path: rule,
+                    source: Box::new(e),
+                })
+            }
+        };
+
+        if meta.always_apply == Some(true) {
+            always_applied = true;
+        }
+        for glob in meta.globs.unwrap_or_default() {
+            match Glob::new(&glob) {
+                Ok(compiled) => {
+                    builder.add(compiled);
+                }
+                Err(source) => {
+                    return Err(RulerError::Rule {
+                        path: rule,
+                        source: Box::new(RulerError::InvalidGlob { glob, source }),
+                    })
+                }
+            }
+        }
+    }
+
+    let globs = builder.build()?;
+    Ok((globs, always_applied))
+}
+
+/// Renders the human-readable summary printed by `ruler coverage`.
+pub fn render_coverage(report: &CoverageReport, list_uncovered: bool) -> String {
+    let mut text = format!(
+        "Covered: {} file(s)\nUncovered: {} file(s)\nCoverage: {:.1}%\n",
+        report.covered.len(),
+        report.uncovered.len(),
+        report.percent()
+    );
+    if list_uncovered && !report.uncovered.is_empty() {
+        text.push_str("\nUncovered files:\n");
+        for path in &report.uncovered {
+            text.push_str(&format!("  {}\n", path.display()));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_coverage, render_coverage};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_coverage_reports_partial_coverage() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path().join("rules");
+        let project = temp.path().join("project");
+        fs::create_dir_all(&rules).unwrap();
+        fs::create_dir_all(project.join("src/nested")).unwrap();
+        fs::create_dir_all(project.join("docs")).unwrap();
+        fs::write(rules.join("rust.mdc"), "---\nglobs: \"*.rs\"\n---\n\nRust.").unwrap();
+        fs::write(
+            rules.join("docs.mdc"),
+            "---\nglobs: [\"docs/**\"]\n---\n\nDocs.",
+        )
+        .unwrap();
+        fs::write(project.join("src/main.rs"), "").unwrap();
+        fs::write(project.join("src/nested/lib.rs"), "").unwrap();
+        fs::write(project.join("docs/guide.md"), "").unwrap();
+        fs::write(project.join("Cargo.toml"), "").unwrap();
+
+        let report = compute_coverage(&rules, &project, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            report.covered,
+            vec![
+                PathBuf::from("docs/guide.md"),
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("src/nested/lib.rs"),
+            ]
+        );
+        assert_eq!(report.uncovered, vec![PathBuf::from("Cargo.toml")]);
+        assert_eq!(
+            render_coverage(&report, true),
+            "Covered: 3 file(s)\nUncovered: 1 file(s)\nCoverage: 75.0%\n\nUncovered files:\n  Cargo.toml\n"
+        );
+    }
+
+    #[test]
+    fn test_always_apply_rule_covers_everything() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path().join("rules");
+        let project = temp.path().join("project");
+        fs::create_dir_all(&rules).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::write(rules.join("all.mdc"), "---\nalwaysApply: true\n---\n\nAll.").unwrap();
+        fs::write(project.join("README.md"), "").unwrap();
+
+        let report = compute_coverage(&rules, &project, &ConvertOptions::default()).unwrap();
+        assert!(report.uncovered.is_empty());
+        assert_eq!(report.covered.len(), 1);
+    }
+}
@@ -0,0 +1,182 @@
+//! `_defaults.mdc`: frontmatter shared by the Cursor rules in one directory.
+//!
+//! Every top-level key of the defaults rule that a sibling rule doesn't set
+//! itself is added to that rule's frontmatter before it is converted, so a
+//! rule's own `description` or `globs` always wins. The defaults rule is not
+//! converted on its own, and rules in subdirectories don't inherit from it.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::common::{parse_frontmatter_with_field_info, raw_body, read_source_file, top_level_key};
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+/// File name of the rule holding a directory's default frontmatter.
+pub const DEFAULTS_RULE: &str = "_defaults.mdc";
+
+/// Whether `path` is a directory's defaults rule rather than a rule to convert.
+pub fn is_defaults_rule(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == DEFAULTS_RULE)
+}
+
+/// The `changed` files with each defaults rule among them replaced by the
+/// rules of `rules` in its directory, which inherit from it. Comes back in
+/// path order without duplicates.
+pub fn expand_changed_defaults(changed: Vec<PathBuf>, rules: &[PathBuf]) -> Vec<PathBuf> {
+    let mut expanded = BTreeSet::new();
+    for path in changed {
+        if is_defaults_rule(&path) {
+            expanded.extend(
+                rules
+                    .iter()
+                    .filter(|rule| rule.parent() == path.parent())
+                    .cloned(),
+            );
+        } else {
+            expanded.insert(path);
+        }
+    }
+    expanded.into_iter().collect()
+}
+
+/// Reads the Cursor rule at `path` like [`read_source_file`], with the
+/// frontmatter it inherits from a `_defaults.mdc` beside it.
+pub fn read_cursor_rule(path: &Path, options: &ConvertOptions) -> Result<String> {
+    let content = read_source_file(path, options)?;
+    let defaults = match path.parent().map(|dir| dir.join(DEFAULTS_RULE)) {
+        Some(defaults) if defaults.is_file() => defaults,
+        _ => return Ok(content),
+    };
+    inherit_defaults(&content, &defaults, options)
+}
+
+// `content` with the top-level keys of the defaults rule at `defaults` that
+// it doesn't set itself appended to its frontmatter. The body is kept byte
+// for byte.
+fn inherit_defaults(content: &str, defaults: &Path, options: &ConvertOptions) -> Result<String> {
+    let defaults_content = read_source_file(defaults, options)?;
+    let (Some(defaults_fm), _, defaults_info) =
+        parse_frontmatter_with_field_info(&defaults_content)?
+    else {
+        return Ok(content.to_string());
+    };
+    let (frontmatter, _, field_info) = parse_frontmatter_with_field_info(content)?;
+    if frontmatter.is_some() && field_info.format != defaults_info.format {
+        return Err(RulerError::DefaultsFormat(defaults.to_path_buf()));
+    }
+
+    let own_keys: HashSet<&str> = field_info.key_order.iter().map(String::as_str).collect();
+    let mut inherited = String::new();
+    let mut inherits = false;
+    // A comment belongs to the key below it, continuation lines to the key above
+    let mut comments = String::new();
+    for line in defaults_fm.lines() {
+        if line.starts_with('#') {
+            comments.push_str(line);
+            comments.push('\n');
+            continue;
+        }
+        if let Some(key) = top_level_key(line) {
+            inherits = !own_keys.contains(key);
+            if inherits {
+                inherited.push_str(&comments);
+            }
+            comments.clear();
+        }
+        if inherits {
+            inherited.push_str(line);
+            inherited.push('\n');
+        }
+    }
+    if inherited.is_empty() {
+        return Ok(content.to_string());
+    }
+
+    let delimiter = defaults_info.format.delimiter();
+    let header = match frontmatter {
+        Some(fm) => format!("{}\n{}\n{}{}\n", delimiter, fm, inherited, delimiter),
+        None => format!("{}\n{}{}\n\n", delimiter, inherited, delimiter),
+    };
+    Ok(format!(
+        "{}{}",
+        field_info.line_ending.apply(&header),
+        raw_body(content)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_changed_defaults, read_cursor_rule};
+    use crate::parser::c2g::convert_cursor_to_github;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_rules_inherit_directory_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(from_dir.join("api/nested")).unwrap();
+        fs::write(
+            from_dir.join("api/_defaults.mdc"),
+            "---\ndescription: API conventions\n# Every handler\nglobs:\n  - \"src/api/**\"\n---\n",
+        )
+        .unwrap();
+        fs::write(from_dir.join("api/plain.mdc"), "Plain.").unwrap();
+        fs::write(
+            from_dir.join("api/own.mdc"),
+            "---\ndescription: Own description\n---\n\nOwn.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("api/nested/deep.mdc"),
+            "---\ndescription: Deep\n---\n\nDeep.",
+        )
+        .unwrap();
+
+        let options = ConvertOptions::default();
+        assert_eq!(
+            read_cursor_rule(&from_dir.join("api/own.mdc"), &options).unwrap(),
+            "---\ndescription: Own description\n# Every handler\nglobs:\n  - \"src/api/**\"\n---\n\nOwn."
+        );
+
+        let to_dir = temp.path().join("out");
+        convert_cursor_to_github(&from_dir, &to_dir, &options).unwrap();
+        let plain = fs::read_to_string(to_dir.join("api/plain.instructions.md")).unwrap();
+        assert!(plain.contains("description: \"API conventions\"\n"));
+        assert!(plain.contains("applyTo: \"src/api/**\"\n"));
+        let own = fs::read_to_string(to_dir.join("api/own.instructions.md")).unwrap();
+        assert!(own.contains("description: \"Own description\"\n"));
+        assert!(own.contains("applyTo: \"src/api/**\"\n"));
+        let deep = fs::read_to_string(to_dir.join("api/nested/deep.instructions.md")).unwrap();
+        assert!(!deep.contains("applyTo: \"src/api/**\""));
+        assert!(!to_dir.join("api/_defaults.instructions.md").exists());
+    }
+
+    #[test]
+    fn test_changed_defaults_refresh_their_siblings() {
+        let rules: Vec<PathBuf> = [
+            "rules/a.mdc",
+            "rules/api/b.mdc",
+            "rules/api/c.mdc",
+            "rules/api/deep/d.mdc",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        let changed = vec![
+            PathBuf::from("rules/api/_defaults.mdc"),
+            PathBuf::from("rules/a.mdc"),
+            PathBuf::from("rules/api/b.mdc"),
+        ];
+        assert_eq!(
+            expand_changed_defaults(changed, &rules),
+            vec![
+                PathBuf::from("rules/a.mdc"),
+                PathBuf::from("rules/api/b.mdc"),
+                PathBuf::from("rules/api/c.mdc"),
+            ]
+        );
+    }
+}
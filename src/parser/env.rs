@@ -0,0 +1,91 @@
+//! `--expand-env`: `${VAR}` placeholders in rule bodies filled in from the
+//! process environment at conversion time.
+
+use std::env;
+
+use super::common::parse_frontmatter;
+use crate::error::{Result, RulerError};
+
+/// Replaces each `${VAR}` in the body of `content` with the value of the
+/// environment variable `VAR`, leaving the frontmatter as written. A variable
+/// that isn't set keeps its placeholder, or fails the conversion when `strict`.
+pub fn expand_env(content: &str, strict: bool) -> Result<String> {
+    let (_, body) = parse_frontmatter(content)?;
+    let content_end = content.trim_end();
+    if body.is_empty() || !content_end.ends_with(&body) {
+        return Ok(content.to_string());
+    }
+
+    let (head, body) = content.split_at(content_end.len() - body.len());
+    let mut expanded = head.to_string();
+    let mut rest = body;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(name) = placeholder_name(after) else {
+            expanded.push_str("${");
+            rest = after;
+            continue;
+        };
+        match env::var(name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) if strict => return Err(RulerError::UndefinedVariable(name.to_string())),
+            Err(_) => expanded.push_str(&rest[start..start + name.len() + 3]),
+        }
+        rest = &after[name.len() + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// The variables of the `${VAR}` placeholders in `text` that aren't set, in
+/// order of appearance.
+pub fn undefined_variables(text: &str) -> Vec<String> {
+    let mut undefined: Vec<String> = Vec::new();
+    for (start, _) in text.match_indices("${") {
+        if let Some(name) = placeholder_name(&text[start + 2..]) {
+            if env::var(name).is_err() && !undefined.iter().any(|n| n == name) {
+                undefined.push(name.to_string());
+            }
+        }
+    }
+    undefined
+}
+
+// The variable name of a placeholder whose `${` was just consumed: letters,
+// digits and underscores up to the closing `}`, not starting with a digit.
+fn placeholder_name(after: &str) -> Option<&str> {
+    let name = &after[..after.find('}')?];
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_env, undefined_variables};
+    use crate::error::RulerError;
+
+    #[test]
+    fn test_expand_env_fills_set_variables_in_the_body_only() {
+        std::env::set_var("RULER_TEST_PROJECT", "Ruler");
+        std::env::remove_var("RULER_TEST_UNSET");
+        let content = "---\ndescription: \"${RULER_TEST_PROJECT}\"\n---\n\nWelcome to ${RULER_TEST_PROJECT}, ${RULER_TEST_UNSET} and ${not valid}.\n";
+
+        assert_eq!(
+            expand_env(content, false).unwrap(),
+            "---\ndescription: \"${RULER_TEST_PROJECT}\"\n---\n\nWelcome to Ruler, ${RULER_TEST_UNSET} and ${not valid}.\n"
+        );
+        assert_eq!(undefined_variables(content), ["RULER_TEST_UNSET"]);
+
+        let err = expand_env(content, true).unwrap_err();
+        assert!(matches!(err, RulerError::UndefinedVariable(name) if name == "RULER_TEST_UNSET"));
+        assert_eq!(
+            expand_env("Plain ${RULER_TEST_PROJECT}", true).unwrap(),
+            "Plain Ruler"
+        );
+    }
+}
@@ -1,128 +1,452 @@
-use anyhow::{Context, Result};
+use log::debug;
 use std::fs;
 use std::path::Path;
 
+use super::batch::{run_batch, Converted};
 use super::common::{
-    find_github_files, parse_frontmatter,
-    CursorMetadata, GithubMetadata
+    check_source_dir, deserialize_frontmatter, find_github_files, normalize_globs, normalize_tags,
+    parse_frontmatter_with_field_info, read_source_file, reorder_fields, restore_comments,
+    serialize_frontmatter, split_globs, target_name_g2c, with_source_body, write_target,
+    CursorMetadata, GithubMetadata,
 };
+use super::merge::{merge_files_into_file, RuleSection};
+use super::options::{ConvertOptions, FrontmatterFormat};
+use crate::error::{Result, RulerError};
 
-pub fn convert_github_to_cursor(from_dir: &Path, to_dir: &Path) -> Result<()> {
-    println!("Converting GitHub Copilot instructions to Cursor rules...");
-    println!("From: {}", from_dir.display());
-    println!("To: {}", to_dir.display());
+pub fn convert_github_to_cursor(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    options.validate_target_extension()?;
+    options.validate_always_apply_glob()?;
+    check_source_dir(from_dir)?;
+    if let Some(target) = &options.output_single {
+        return merge_github_instructions(from_dir, target, options);
+    }
+    if options.shows_progress() {
+        println!("Converting GitHub Copilot instructions to Cursor rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", to_dir.display());
+    }
 
-    // Create target directory if it doesn't exist
-    fs::create_dir_all(to_dir)
-        .with_context(|| format!("Failed to create directory: {}", to_dir.display()))?;
+    // Create target directory if it doesn't exist; check mode writes nothing
+    if !options.check {
+        fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+            path: to_dir.to_path_buf(),
+            source,
+        })?;
+    }
 
     // Find all .md and .instructions.md files in the source directory
-    let source_files = find_github_files(from_dir)?;
+    let source_files = find_github_files(from_dir, options)?;
 
-    if source_files.is_empty() {
+    if source_files.is_empty() && options.shows_progress() {
         println!("No .md or .instructions.md files found in {}", from_dir.display());
-        return Ok(());
     }
 
-    let mut success_count = 0;
-    let mut error_count = 0;
+    run_batch(
+        from_dir,
+        to_dir,
+        Some(".mdc"),
+        source_files,
+        options,
+        |relative_path| target_name_g2c(relative_path, options),
+        |source_file, _relative_path, target_path| {
+            convert_md_to_mdc(source_file, target_path, options)
+                .map(|written| Converted::target(target_path, written))
+        },
+    )
+}
 
-    for source_file in source_files {
-        let relative_path = source_file
-            .strip_prefix(from_dir)
-            .with_context(|| "Failed to get relative path")?;
+// `--output-single`: concatenates the GitHub instructions in `from_dir` into
+// the one Markdown file `target`, a `## <title>` section per instruction in
+// source path order.
+fn merge_github_instructions(
+    from_dir: &Path,
+    target: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Merging GitHub Copilot instructions into one file...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", target.display());
+    }
 
-        // Change extension from .instructions.md/.md to .mdc
-        let mut target_path = to_dir.join(relative_path);
-        if let Some(file_name) = target_path.file_name().and_then(|n| n.to_str()) {
-            if let Some(base_name) = file_name.strip_suffix(".instructions.md") {
-                target_path.set_file_name(format!("{}.mdc", base_name));
-            } else if let Some(base_name) = file_name.strip_suffix(".md") {
-                target_path.set_file_name(format!("{}.mdc", base_name));
-            } else {
-                // Fallback
-                target_path.set_extension("mdc");
-            }
-        }
+    let source_files = find_github_files(from_dir, options)?;
+    merge_files_into_file(
+        from_dir,
+        &source_files,
+        target,
+        options,
+        |source_file, relative| read_instruction_section(source_file, relative, options).map(Some),
+    )
+}
 
-        // Create parent directories if they don't exist
-        if let Some(parent) = target_path.parent() {
-            if let Err(e) = fs::create_dir_all(parent) {
-                eprintln!("Error creating directory {}: {}", parent.display(), e);
-                continue;
-            }
-        }
+// Reads one instruction as a merged section, titled like `c2g-merge`'s from
+// the rule it would convert to.
+fn read_instruction_section(
+    source_file: &Path,
+    relative: &Path,
+    options: &ConvertOptions,
+) -> Result<(RuleSection, Vec<String>)> {
+    let content = read_source_file(source_file, options)?;
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(&content)?;
+    let github_meta: GithubMetadata = match frontmatter {
+        Some(fm) => deserialize_frontmatter(&fm, field_info.format, "GitHub")?,
+        None => Default::default(),
+    };
+    // No `priority`, so the sections stay in path order
+    let meta = CursorMetadata {
+        name: github_meta.name,
+        description: github_meta.description,
+        ..Default::default()
+    };
+    let rule = target_name_g2c(relative, options);
+    Ok((RuleSection::from_rule(&meta, body, &rule), Vec::new()))
+}
 
-        match convert_md_to_mdc(&source_file, &target_path) {
-            Ok(()) => {
-                println!(
-                    "Converted: {} -> {}",
-                    source_file.display(),
-                    target_path.display()
-                );
-                success_count += 1;
-            }
-            Err(e) => {
-                eprintln!("Error converting {}: {}", source_file.display(), e);
-                error_count += 1;
-                continue;
-            }
-        }
-    }
+fn convert_md_to_mdc(source: &Path, target: &Path, options: &ConvertOptions) -> Result<bool> {
+    let content = read_source_file(source, options)?;
 
-    if error_count > 0 {
-        println!(
-            "Conversion completed with {} successes and {} errors.",
-            success_count, error_count
-        );
-    } else {
-        println!("Conversion completed successfully!");
+    let output_content = convert_github_content_with_options(&content, options)?;
+    let output_content = with_source_body(&content, output_content, options);
+
+    write_target(target, &output_content, options)
+}
+
+// `applyTo` patterns that match every file and so mean `alwaysApply: true`,
+// along with any `--always-apply-glob`.
+const CATCH_ALL_PATTERNS: &[&str] = &["**", "**/*", "*"];
+
+fn is_catch_all(apply_to: &str, options: &ConvertOptions) -> bool {
+    let apply_to = apply_to.trim();
+    CATCH_ALL_PATTERNS.contains(&apply_to) || apply_to == options.always_apply_glob().trim()
+}
+
+// Undoes c2g's rewrite of `.mdc` globs, so a glob aimed at instruction files
+// targets the rule files they came from.
+fn cursor_glob(pattern: &str, suffix: &str) -> String {
+    let pattern = pattern.trim();
+    match pattern.strip_suffix(suffix) {
+        Some(stem) => format!("{}.mdc", stem),
+        None => pattern.to_string(),
     }
-    Ok(())
 }
 
-fn convert_md_to_mdc(source: &Path, target: &Path) -> Result<()> {
-    let content = fs::read_to_string(source)
-        .with_context(|| format!("Failed to read file: {}", source.display()))?;
+/// Converts the raw contents of a GitHub Copilot instruction into Cursor rule contents.
+pub fn convert_github_content(content: &str) -> Result<String> {
+    convert_github_content_with_options(content, &ConvertOptions::default())
+}
 
-    let (frontmatter, body) = parse_frontmatter(&content)?;
+/// Like [`convert_github_content`], honoring the content-level `options`.
+pub fn convert_github_content_with_options(
+    content: &str,
+    options: &ConvertOptions,
+) -> Result<String> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
 
     // Convert GitHub metadata to Cursor metadata
     let cursor_metadata = if let Some(fm) = frontmatter {
+        debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
         let github_meta: GithubMetadata =
-            serde_yaml::from_str(&fm).with_context(|| "Failed to parse GitHub frontmatter")?;
+            deserialize_frontmatter(&fm, field_info.format, "GitHub")?;
+        debug!("parsed {:?}", github_meta);
 
-        let mut cursor_meta = CursorMetadata::default();
-        cursor_meta.description = github_meta.description;
+        let mut cursor_meta = CursorMetadata {
+            name: github_meta.name,
+            description: github_meta.description,
+            authors: github_meta.authors,
+            tags: if options.normalize_tags {
+                github_meta.tags.as_deref().map(normalize_tags)
+            } else {
+                github_meta.tags
+            },
+            version: github_meta.version,
+            priority: github_meta.priority,
+            github: (!github_meta.extra.is_empty()).then_some(github_meta.extra),
+            ..Default::default()
+        };
 
-        // Convert applyTo to globs and alwaysApply
-        if let Some(apply_to) = github_meta.apply_to {
-            if apply_to == "**" {
+        // Convert applyTo to globs and alwaysApply. An instruction with no
+        // or an empty `applyTo` is only attached on request, like
+        // `alwaysApply: false`
+        cursor_meta.always_apply = Some(false);
+        if let Some(apply_to) = github_meta.apply_to.filter(|a| !a.trim().is_empty()) {
+            if is_catch_all(&apply_to, options) {
                 cursor_meta.always_apply = Some(true);
                 cursor_meta.globs = Some(vec![]);
             } else {
-                cursor_meta.always_apply = Some(false);
                 cursor_meta.globs =
-                    Some(apply_to.split(',').map(|s| s.trim().to_string()).collect());
+                    Some(normalize_globs(split_globs(&apply_to).into_iter().map(
+                        |glob| cursor_glob(glob, options.instructions_suffix()),
+                    )));
             }
+            debug!(
+                "applyTo: {:?} mapped to alwaysApply: {:?}, globs: {:?}",
+                apply_to, cursor_meta.always_apply, cursor_meta.globs
+            );
         }
 
         Some(cursor_meta)
     } else {
+        debug!("no frontmatter detected");
         None
     };
 
-    // Write the converted file
-    let output_content = if let Some(meta) = cursor_metadata {
-        let frontmatter_yaml =
-            serde_yaml::to_string(&meta).with_context(|| "Failed to serialize Cursor metadata")?;
-        format!("---\n{}---\n\n{}", frontmatter_yaml, body)
+    let output_content = if let Some(mut meta) = cursor_metadata {
+        let format = options.frontmatter_format.unwrap_or(field_info.format);
+        let renames = [("applyTo", "globs")];
+
+        // A field the source listed without a value stays an empty placeholder
+        let empty_description =
+            field_info.description_present && meta.description.as_deref().is_none_or(str::is_empty);
+        let empty_globs =
+            field_info.apply_to_present && meta.globs.is_none() && meta.always_apply != Some(true);
+        if empty_description {
+            meta.description = Some(String::new());
+        }
+        if empty_globs {
+            meta.globs = Some(Vec::new());
+        }
+        let mut serialized = serialize_frontmatter(&meta, format, "Cursor")?;
+        if format == FrontmatterFormat::Yaml {
+            serialized = serialized
+                .lines()
+                .map(|line| match line {
+                    "description: ''" if empty_description => "description:",
+                    "globs: []" if empty_globs => "globs:",
+                    _ => line,
+                })
+                .map(|line| format!("{}\n", line))
+                .collect();
+        }
+
+        let frontmatter = restore_comments(
+            &reorder_fields(&serialized, &field_info.key_order, &renames),
+            &field_info.comments,
+            &renames,
+        );
+        let delimiter = format.delimiter();
+        let header = field_info
+            .line_ending
+            .apply(&format!("{}\n{}{}\n\n", delimiter, frontmatter, delimiter));
+        format!("{}{}", header, body)
     } else {
         body
     };
 
-    fs::write(target, output_content)
-        .with_context(|| format!("Failed to write file: {}", target.display()))?;
+    Ok(output_content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_github_content, convert_github_content_with_options, convert_github_to_cursor,
+    };
+    use crate::parser::c2g::convert_cursor_content_with_options;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+
+    #[test]
+    fn test_convert_github_content_maps_apply_to_to_globs() {
+        let input = "---\ndescription: \"Python\"\napplyTo: \"*.py,*.pyx\"\n---\n\nUse type hints.";
+        let output = convert_github_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: Python\nglobs:\n- '*.py'\n- '*.pyx'\nalwaysApply: false\n---\n\nUse type hints."
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn test_apply_to_list_maps_to_globs() {
+        let input = "---\napplyTo:\n  - \"src/**/*.py\"\n  - \"tests/**\"\n---\n\nBody.";
+        let output = convert_github_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\nglobs:\n- src/**/*.py\n- tests/**\nalwaysApply: false\n---\n\nBody."
+        );
+
+        let output = convert_github_content("---\napplyTo: [\"**\"]\n---\n\nAll.").unwrap();
+        assert!(output.contains("alwaysApply: true\n"));
+        let output =
+            convert_github_content("+++\napplyTo = [\"*.rs\", \"*.toml\"]\n+++\n\nRust.").unwrap();
+        assert!(output.contains("globs = [\"*.rs\", \"*.toml\"]\n"));
+    }
+
+    #[test]
+    fn test_scalar_apply_to_is_kept_as_text() {
+        let output = convert_github_content("---\napplyTo: 2024\n---\n\nBody.").unwrap();
+        assert_eq!(
+            output,
+            "---\nglobs:\n- '2024'\nalwaysApply: false\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_braced_apply_to_round_trips() {
+        let input = "---\nglobs: [\"src/{a,b}/**\", \"*.{ts,tsx}\"]\n---\n\nBody.";
+        let github =
+            convert_cursor_content_with_options(input, &ConvertOptions::default(), None).unwrap();
+        assert_eq!(
+            github,
+            "---\napplyTo: \"src/{a,b}/**,*.{ts,tsx}\"\n---\n\nBody."
+        );
+        let cursor = convert_github_content(&github).unwrap();
+        assert!(cursor.contains("globs:\n- src/{a,b}/**\n- '*.{ts,tsx}'\n"));
+    }
+
+    #[test]
+    fn test_duplicate_and_padded_apply_to_entries_are_dropped() {
+        let input = "---\napplyTo: \"src/** , *.py,src/**,, *.py \"\n---\n\nBody.";
+        let output = convert_github_content(input).unwrap();
+        assert!(output.contains("globs:\n- src/**\n- '*.py'\nalwaysApply: false\n"));
+    }
+
+    #[test]
+    fn test_catch_all_apply_to_becomes_always_apply() {
+        for apply_to in ["**", "**/*", "*", " ** ", "**/* "] {
+            let input = format!("---\napplyTo: \"{}\"\n---\n\nEverywhere.", apply_to);
+            let output = convert_github_content(&input).unwrap();
+            assert_eq!(
+                output, "---\nglobs: []\nalwaysApply: true\n---\n\nEverywhere.",
+                "applyTo: {:?}",
+                apply_to
+            );
+        }
+
+        let output = convert_github_content("---\napplyTo: \"**/*.rs\"\n---\n\nRust.").unwrap();
+        assert!(output.contains("alwaysApply: false\n"));
+    }
+
+    #[test]
+    fn test_custom_always_apply_glob_round_trips() {
+        let options = ConvertOptions {
+            always_apply_glob: Some("./**".to_string()),
+            ..Default::default()
+        };
+        let github = convert_cursor_content_with_options(
+            "---\nalwaysApply: true\n---\n\nEverywhere.",
+            &options,
+            None,
+        )
+        .unwrap();
+        assert_eq!(github, "---\napplyTo: \"./**\"\n---\n\nEverywhere.");
+
+        let cursor = convert_github_content_with_options(&github, &options).unwrap();
+        assert!(cursor.contains("alwaysApply: true\n"));
+        // Without the option the token is an ordinary glob
+        let cursor = convert_github_content(&github).unwrap();
+        assert!(cursor.contains("globs:\n- ./**\n"));
+    }
+
+    #[test]
+    fn test_instruction_globs_and_missing_apply_to_round_trip() {
+        let output =
+            convert_github_content("---\napplyTo: \"[name].instructions.md,*.ts\"\n---\n\nBody.")
+                .unwrap();
+        assert!(output.contains("globs:\n- '[name].mdc'\n- '*.ts'\n"));
+
+        let output =
+            convert_github_content("---\ndescription: \"On request\"\n---\n\nBody.").unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: On request\nalwaysApply: false\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_unknown_github_keys_survive_a_round_trip() {
+        let input = "---\ntitle: \"Python style\"\napplyTo: \"*.py\"\nx-team: platform\n---\n\nUse type hints.";
+        let cursor = convert_github_content(input).unwrap();
+        assert_eq!(
+            cursor,
+            "---\nglobs:\n- '*.py'\nalwaysApply: false\ngithub:\n  title: Python style\n  x-team: platform\n---\n\nUse type hints."
+        );
+
+        let github =
+            convert_cursor_content_with_options(&cursor, &ConvertOptions::default(), None).unwrap();
+        assert_eq!(
+            github,
+            "---\napplyTo: \"*.py\"\ntitle: Python style\nx-team: platform\n---\n\nUse type hints."
+        );
+    }
+
+    #[test]
+    fn test_empty_github_fields_stay_empty_placeholders() {
+        let output = convert_github_content("---\ndescription:\napplyTo:\n---\n\nBody.").unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription:\nglobs:\nalwaysApply: false\n---\n\nBody."
+        );
+
+        let output =
+            convert_github_content("+++\ndescription = \"\"\napplyTo = \"\"\n+++\n\nBody.")
+                .unwrap();
+        assert_eq!(
+            output,
+            "+++\ndescription = \"\"\nglobs = []\nalwaysApply = false\n+++\n\nBody."
+        );
+
+        // Absent fields stay absent
+        let output = convert_github_content("---\nname: x\n---\n\nBody.").unwrap();
+        assert_eq!(output, "---\nname: x\nalwaysApply: false\n---\n\nBody.");
+    }
+
+    #[test]
+    fn test_convert_github_content_preserves_crlf() {
+        let input = "---\r\napplyTo: \"*.cs\"\r\n---\r\n\r\nLine one.\r\nLine two.";
+        let output = convert_github_content(input).unwrap();
+        assert_eq!(
+            output,
+            "---\r\nglobs:\r\n- '*.cs'\r\nalwaysApply: false\r\n---\r\n\r\nLine one.\r\nLine two."
+        );
+    }
+
+    #[test]
+    fn test_convert_github_content_toml_frontmatter() {
+        let input = "+++\ndescription = \"Rust\"\napplyTo = \"*.rs\"\n+++\n\nUse clippy.";
+        let output = convert_github_content(input).unwrap();
+        assert_eq!(
+            output,
+            "+++\ndescription = \"Rust\"\nglobs = [\"*.rs\"]\nalwaysApply = false\n+++\n\nUse clippy."
+        );
+    }
+
+    #[test]
+    fn test_convert_github_content_universal_apply() {
+        let input = "---\napplyTo: \"**\"\n---\n\nEverywhere.";
+        let output = convert_github_content(input).unwrap();
+        assert!(output.contains("alwaysApply: true"));
+    }
+
+    #[test]
+    fn test_output_single_concatenates_instructions_in_path_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("instructions");
+        fs::create_dir_all(from_dir.join("b")).unwrap();
+        fs::write(
+            from_dir.join("b/second.instructions.md"),
+            "---\ndescription: \"Second rule\"\napplyTo: \"*.rs\"\npriority: 10\n---\n\nSecond body.\n",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("a.instructions.md"),
+            "---\nname: \"Alpha\"\n---\n\nFirst body.",
+        )
+        .unwrap();
+        fs::write(from_dir.join("c.md"), "Third body.\n").unwrap();
+
+        let target = temp.path().join("out/all.md");
+        let options = ConvertOptions {
+            output_single: Some(target.clone()),
+            ..Default::default()
+        };
+        convert_github_to_cursor(&from_dir, &temp.path().join("unused"), &options).unwrap();
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "## Alpha\n\nFirst body.\n\n## Second rule\n\nSecond body.\n\n## c\n\nThird body.\n"
+        );
+        assert!(!temp.path().join("unused").exists());
+    }
 }
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::common::parse_frontmatter;
+use crate::error::{Result, RulerError};
+
+const INCLUDE_DIRECTIVE: &str = "@include ";
+
+/// Expands `@include <path>` lines in `content`, where `path` is relative to the
+/// file containing the directive. Included files have their frontmatter stripped
+/// and are expanded recursively; an include cycle is reported with the full chain
+/// (`a.mdc -> b.mdc -> a.mdc`) instead of recursing forever.
+pub fn expand_includes(path: &Path, content: &str) -> Result<String> {
+    let mut stack = vec![canonical(path)];
+    let mut chain = vec![path.to_path_buf()];
+    expand_with_stack(path, content, &mut stack, &mut chain)
+}
+
+fn expand_with_stack(
+    path: &Path,
+    content: &str,
+    stack: &mut Vec<PathBuf>,
+    chain: &mut Vec<PathBuf>,
+) -> Result<String> {
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut expanded = Vec::new();
+
+    for line in content.lines() {
+        let Some(include) = line.trim().strip_prefix(INCLUDE_DIRECTIVE) else {
+            expanded.push(line.to_string());
+            continue;
+        };
+
+        let include_path = base_dir.join(include.trim());
+        let key = canonical(&include_path);
+        if stack.contains(&key) {
+            let mut cycle = chain.clone();
+            cycle.push(include_path);
+            return Err(RulerError::IncludeCycle(cycle));
+        }
+
+        let included = fs::read_to_string(&include_path).map_err(|source| RulerError::Read {
+            path: include_path.clone(),
+            source,
+        })?;
+        let (_, body) = parse_frontmatter(&included)?;
+
+        stack.push(key);
+        chain.push(include_path.clone());
+        let body = expand_with_stack(&include_path, &body, stack, chain)?;
+        stack.pop();
+        chain.pop();
+
+        expanded.push(body);
+    }
+
+    let mut result = expanded.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+// Canonicalize when possible so `./a.mdc` and `a.mdc` are recognized as the same file.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_includes;
+    use std::fs;
+
+    #[test]
+    fn test_expand_includes_inlines_body() {
+        let temp = tempfile::tempdir().unwrap();
+        let shared = temp.path().join("shared.mdc");
+        fs::write(&shared, "---\ndescription: \"Shared\"\n---\n\nShared text.").unwrap();
+        let main = temp.path().join("main.mdc");
+        let content = "Intro.\n@include shared.mdc\nOutro.\n";
+
+        let expanded = expand_includes(&main, content).unwrap();
+        assert_eq!(expanded, "Intro.\nShared text.\nOutro.\n");
+    }
+
+    #[test]
+    fn test_expand_includes_detects_cycle() {
+        let temp = tempfile::tempdir().unwrap();
+        let a = temp.path().join("a.mdc");
+        let b = temp.path().join("b.mdc");
+        fs::write(&a, "A body.\n@include b.mdc\n").unwrap();
+        fs::write(&b, "B body.\n@include a.mdc\n").unwrap();
+
+        let content = fs::read_to_string(&a).unwrap();
+        let err = expand_includes(&a, &content).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Include cycle detected: {} -> {} -> {}",
+                a.display(),
+                b.display(),
+                a.display()
+            )
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Detecting the programming language a rule targets from its globs.
+
+// Language name (used as the instruction file stem) and its file extensions.
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("typescript", &["ts", "tsx", "mts", "cts"]),
+    ("javascript", &["js", "jsx", "mjs", "cjs"]),
+    ("python", &["py", "pyi"]),
+    ("rust", &["rs"]),
+    ("go", &["go"]),
+    ("java", &["java"]),
+    ("kotlin", &["kt", "kts"]),
+    ("ruby", &["rb"]),
+    ("csharp", &["cs"]),
+    ("cpp", &["cpp", "cc", "cxx", "hpp", "hh"]),
+    ("c", &["c", "h"]),
+    ("swift", &["swift"]),
+    ("php", &["php"]),
+    ("shell", &["sh", "bash", "zsh"]),
+    ("html", &["html", "htm"]),
+    ("css", &["css", "scss", "sass", "less"]),
+    ("sql", &["sql"]),
+    ("markdown", &["md", "mdx"]),
+];
+
+/// Languages targeted by `glob`, judged by the extension of its last path
+/// segment. Brace alternatives such as `*.{ts,py}` may name several. Globs
+/// without a known extension, like `**` or `Makefile`, yield none.
+pub fn glob_languages(glob: &str) -> Vec<&'static str> {
+    let last = glob.rsplit('/').next().unwrap_or(glob);
+    let extensions: Vec<&str> = match last.rfind(".{") {
+        Some(start) if last.ends_with('}') => last[start + 2..last.len() - 1].split(',').collect(),
+        _ => last
+            .rsplit_once('.')
+            .map(|(_, ext)| ext)
+            .into_iter()
+            .collect(),
+    };
+
+    let mut languages = Vec::new();
+    for ext in extensions {
+        let language = LANGUAGES
+            .iter()
+            .find(|(_, exts)| exts.iter().any(|e| e.eq_ignore_ascii_case(ext.trim())));
+        if let Some((name, _)) = language {
+            if !languages.contains(name) {
+                languages.push(*name);
+            }
+        }
+    }
+    languages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_languages;
+
+    #[test]
+    fn test_glob_languages() {
+        assert_eq!(glob_languages("src/**/*.tsx"), vec!["typescript"]);
+        assert_eq!(glob_languages("*.{py,pyi}"), vec!["python"]);
+        assert_eq!(glob_languages("**/*.{ts,rs}"), vec!["typescript", "rust"]);
+        assert!(glob_languages("**").is_empty());
+        assert!(glob_languages("Makefile").is_empty());
+        assert!(glob_languages("docs/*.*").is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use globset::Glob;
+
+use super::common::{
+    count_unreadable_globs, parse_cursor_metadata, parse_frontmatter_with_field_info, GITHUB_FIELDS,
+};
+use super::env::undefined_variables;
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+/// Validates the globs of a Cursor rule before it is converted.
+///
+/// Returns a warning per suspicious pattern. Checks that `options` turn into
+/// hard failures (such as `--no-absolute-globs`, or `--strict` for globs that
+/// don't compile or globs on an `alwaysApply: true` rule) return an error
+/// instead.
+pub fn lint_cursor_rule(content: &str, options: &ConvertOptions) -> Result<Vec<String>> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+    let mut warnings = Vec::new();
+    // `--strict` already failed on these while the source was read
+    if options.expand_env {
+        for name in undefined_variables(&body) {
+            warnings.push(format!(
+                "`${{{}}}` is not set and was left as written",
+                name
+            ));
+        }
+    }
+    let fm = match frontmatter {
+        Some(fm) => fm,
+        None => return Ok(warnings),
+    };
+    let meta = parse_cursor_metadata(&fm, field_info.format)?;
+    // c2g writes these from the rule's own fields and drops the block's copy
+    for key in meta.github.iter().flat_map(|github| github.keys()) {
+        if GITHUB_FIELDS.contains(&key.as_str()) {
+            warnings.push(format!(
+                "`github.{}` is ignored; `{}` is converted from the rule itself",
+                key, key
+            ));
+        }
+    }
+    let globs = meta.globs.unwrap_or_default();
+
+    // An always-applied rule ignores its globs, and so does its conversion
+    if meta.always_apply == Some(true) && !globs.is_empty() {
+        if options.strict {
+            return Err(RulerError::AlwaysApplyWithGlobs(globs));
+        }
+        warnings.push(format!(
+            "`alwaysApply: true` overrides `globs`, dropping {}",
+            quoted_globs(&globs)
+        ));
+    }
+    let unreadable = count_unreadable_globs(&fm, field_info.format)?;
+    if unreadable > 0 {
+        warnings.push(format!(
+            "skipped {} `globs` {} (expected a string or a map with a `pattern`/`glob` key)",
+            unreadable,
+            if unreadable == 1 { "entry" } else { "entries" }
+        ));
+    }
+    for glob in &globs {
+        // Globs are scoped to the repository, so a leading `/` is almost
+        // always a copy-pasted filesystem path
+        if glob.starts_with('/') {
+            if options.no_absolute_globs {
+                return Err(RulerError::AbsoluteGlob(glob.clone()));
+            }
+            warnings.push(format!("glob `{}` is an absolute path", glob));
+        }
+        // Invalid patterns are still converted, but no tool will match them
+        if let Err(source) = Glob::new(glob) {
+            if options.strict {
+                return Err(RulerError::InvalidGlob {
+                    glob: glob.clone(),
+                    source,
+                });
+            }
+            warnings.push(format!("glob `{}` is invalid: {}", glob, source.kind()));
+        }
+    }
+
+    Ok(warnings)
+}
+
+fn quoted_globs(globs: &[String]) -> String {
+    globs
+        .iter()
+        .map(|glob| format!("`{}`", glob))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lint_cursor_rule;
+    use crate::parser::options::ConvertOptions;
+
+    #[test]
+    fn test_absolute_glob_warns_or_fails() {
+        let rule = "---\nglobs: [\"src/**\", \"/etc/**\"]\n---\n\nBody.";
+
+        let warnings = lint_cursor_rule(rule, &ConvertOptions::default()).unwrap();
+        assert_eq!(warnings, vec!["glob `/etc/**` is an absolute path"]);
+
+        let options = ConvertOptions {
+            no_absolute_globs: true,
+            ..Default::default()
+        };
+        let error = lint_cursor_rule(rule, &options).unwrap_err();
+        assert!(error.to_string().contains("`/etc/**`"));
+    }
+
+    #[test]
+    fn test_invalid_glob_warns_or_fails_under_strict() {
+        let valid = "---\nglobs: [\"src/**/*.{ts,tsx}\", \"[abc]*.md\"]\n---\n\nBody.";
+        assert!(lint_cursor_rule(valid, &ConvertOptions::default())
+            .unwrap()
+            .is_empty());
+
+        let rule = "---\nglobs: [\"src/**\", \"src/**[\"]\n---\n\nBody.";
+        let warnings = lint_cursor_rule(rule, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["glob `src/**[` is invalid: unclosed character class; missing ']'"]
+        );
+
+        let options = ConvertOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let error = lint_cursor_rule(rule, &options).unwrap_err();
+        assert!(error.to_string().contains("`src/**[`"));
+    }
+
+    #[test]
+    fn test_always_apply_with_globs_warns_or_fails_under_strict() {
+        let rule = "---\nglobs: [\"*.ts\", \"*.tsx\"]\nalwaysApply: true\n---\n\nBody.";
+        let warnings = lint_cursor_rule(rule, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["`alwaysApply: true` overrides `globs`, dropping `*.ts`, `*.tsx`"]
+        );
+
+        let options = ConvertOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let error = lint_cursor_rule(rule, &options).unwrap_err();
+        assert!(error.to_string().contains("`*.ts`, `*.tsx`"));
+
+        let empty = "---\nglobs: []\nalwaysApply: true\n---\n\nBody.";
+        assert!(lint_cursor_rule(empty, &options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_glob_entries_warn() {
+        let rule = "---\nglobs:\n  - \"*.ts\"\n  - pattern: \"*.py\"\n  - 42\n  - {exclude: true}\n---\n\nBody.";
+        let warnings = lint_cursor_rule(rule, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["skipped 2 `globs` entries (expected a string or a map with a `pattern`/`glob` key)"]
+        );
+    }
+
+    #[test]
+    fn test_github_block_keys_the_rule_models_warn() {
+        let rule =
+            "---\ndescription: Style\ngithub:\n  description: Other\n  title: Style\n---\n\nBody.";
+        let warnings = lint_cursor_rule(rule, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                "`github.description` is ignored; `description` is converted from the rule itself"
+            ]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! `ruler list`: a read-only overview of a rule set.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::options::ConvertOptions;
+use super::scan::scan_cursor_dir_with_options;
+use crate::error::Result;
+
+/// When Cursor attaches a rule, following its rule types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// `alwaysApply: true`.
+    Always,
+    /// Attached to files matching the rule's globs.
+    Globs,
+    /// No globs but a description, which the agent reads to decide.
+    AgentRequested,
+    /// Only used when referenced explicitly.
+    Manual,
+}
+
+impl Activation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Activation::Always => "always",
+            Activation::Globs => "globs",
+            Activation::AgentRequested => "agent-requested",
+            Activation::Manual => "manual",
+        }
+    }
+}
+
+/// One rule as printed by `ruler list`.
+#[derive(Debug, Serialize)]
+pub struct RuleSummary {
+    /// Path of the rule relative to the rules directory.
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub activation: Activation,
+    /// The globs a `globs` rule is scoped to; empty otherwise.
+    pub globs: Vec<String>,
+}
+
+/// Summarizes every Cursor rule in `rules_dir`, in path order. A rule whose
+/// frontmatter can't be parsed fails the whole listing.
+pub fn list_rules(rules_dir: &Path, options: &ConvertOptions) -> Result<Vec<RuleSummary>> {
+    let mut rules = Vec::new();
+    for (rule, meta, _) in scan_cursor_dir_with_options(rules_dir, options)? {
+        let description = meta.description.filter(|d| !d.trim().is_empty());
+        let globs = meta.globs.unwrap_or_default();
+        let activation = if meta.always_apply == Some(true) {
+            Activation::Always
+        } else if !globs.is_empty() {
+            Activation::Globs
+        } else if description.is_some() {
+            Activation::AgentRequested
+        } else {
+            Activation::Manual
+        };
+
+        rules.push(RuleSummary {
+            path: rule.strip_prefix(rules_dir).unwrap_or(&rule).to_path_buf(),
+            name: meta.name,
+            description,
+            globs: if activation == Activation::Globs {
+                globs
+            } else {
+                Vec::new()
+            },
+            activation,
+        });
+    }
+    Ok(rules)
+}
+
+/// Renders the table printed by `ruler list`: one row per rule with its path,
+/// activation, name and the first line of its description.
+pub fn render_rule_list(rules: &[RuleSummary]) -> String {
+    let rows: Vec<[String; 4]> = rules
+        .iter()
+        .map(|rule| {
+            let activation = match rule.activation {
+                Activation::Globs => format!("globs: {}", rule.globs.join(", ")),
+                activation => activation.as_str().to_string(),
+            };
+            [
+                rule.path.display().to_string(),
+                activation,
+                rule.name.clone().unwrap_or_else(|| "-".to_string()),
+                rule.description
+                    .as_deref()
+                    .and_then(|d| d.lines().next())
+                    .unwrap_or("-")
+                    .to_string(),
+            ]
+        })
+        .collect();
+
+    let header = ["RULE", "ACTIVATION", "NAME", "DESCRIPTION"].map(str::to_string);
+    let mut widths = [0; 3];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut text = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        );
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text.push_str(&format!("\n{} rule(s)\n", rules.len()));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_rules, render_rule_list, Activation};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+
+    #[test]
+    fn test_list_resolves_each_activation() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path();
+        fs::create_dir_all(rules.join("lang")).unwrap();
+        fs::write(
+            rules.join("style.mdc"),
+            "---\nname: style\ndescription: House style\nglobs: [\"*.ts\"]\nalwaysApply: true\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(
+            rules.join("lang/rust.mdc"),
+            "---\ndescription: Rust\nglobs: \"*.rs,Cargo.toml\"\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(
+            rules.join("review.mdc"),
+            "---\ndescription: |\n  Code review\n  checklist\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(rules.join("snippets.mdc"), "Body.").unwrap();
+
+        let listed = list_rules(rules, &ConvertOptions::default()).unwrap();
+        let activations: Vec<Activation> = listed.iter().map(|rule| rule.activation).collect();
+        assert_eq!(
+            activations,
+            vec![
+                Activation::Globs,
+                Activation::AgentRequested,
+                Activation::Manual,
+                Activation::Always,
+            ]
+        );
+        assert_eq!(
+            render_rule_list(&listed),
+            "RULE           ACTIVATION               NAME   DESCRIPTION\n\
+             lang/rust.mdc  globs: *.rs, Cargo.toml  -      Rust\n\
+             review.mdc     agent-requested          -      Code review\n\
+             snippets.mdc   manual                   -      -\n\
+             style.mdc      always                   style  House style\n\
+             \n4 rule(s)\n"
+        );
+
+        let json = serde_json::to_value(&listed).unwrap();
+        assert_eq!(json[0]["activation"], "globs");
+        assert_eq!(json[0]["globs"][1], "Cargo.toml");
+        assert_eq!(json[3]["globs"].as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,110 @@
+//! `--manifest`: an audit record of every source, its target and the result.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::batch::{json_report, FileOutcome, JsonReport};
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+/// Contents of the file written by `--manifest`: the `--format json` report
+/// plus the conversion mode and when the run finished. Because the report's
+/// fields sit at the top level, a manifest can also be passed to
+/// `--changelog-against`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Conversion mode that produced the targets, e.g. `c2g`.
+    pub mode: Option<String>,
+    /// Seconds since the Unix epoch when the run finished.
+    pub generated_at: u64,
+    #[serde(flatten)]
+    pub report: JsonReport,
+}
+
+/// Builds the manifest for `outcomes`, stamped with the current time.
+pub fn build_manifest(outcomes: &[FileOutcome], options: &ConvertOptions) -> Manifest {
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    Manifest {
+        mode: options.mode.clone(),
+        generated_at,
+        report: json_report(outcomes),
+    }
+}
+
+/// Writes the manifest for `outcomes` to `path` as pretty-printed JSON.
+pub fn write_manifest(
+    path: &Path,
+    outcomes: &[FileOutcome],
+    options: &ConvertOptions,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(&build_manifest(outcomes, options))?;
+    fs::write(path, format!("{}\n", json)).map_err(|source| RulerError::Write {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_manifest;
+    use crate::parser::batch::{ConversionStatus, FileOutcome};
+    use crate::parser::changelog::read_manifest;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_manifest_records_mode_status_and_digest() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("style.instructions.md");
+        fs::write(&target, "Be concise.").unwrap();
+        let outcomes = vec![
+            FileOutcome {
+                source: PathBuf::from("rules/style.mdc"),
+                relative: PathBuf::from("style.mdc"),
+                targets: vec![target],
+                error: None,
+                warnings: Vec::new(),
+                not_utf8: false,
+                unchanged: Vec::new(),
+                kept: false,
+                dropped: Vec::new(),
+            },
+            FileOutcome {
+                source: PathBuf::from("rules/broken.mdc"),
+                relative: PathBuf::from("broken.mdc"),
+                targets: vec![temp.path().join("broken.instructions.md")],
+                error: Some("parse error".to_string()),
+                warnings: Vec::new(),
+                not_utf8: false,
+                unchanged: Vec::new(),
+                kept: false,
+                dropped: Vec::new(),
+            },
+        ];
+        let options = ConvertOptions {
+            mode: Some("c2g".to_string()),
+            ..Default::default()
+        };
+
+        let path = temp.path().join("conversion-manifest.json");
+        write_manifest(&path, &outcomes, &options).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["mode"], "c2g");
+        assert!(json["generated_at"].as_u64().unwrap() > 0);
+        assert_eq!(json["conversions"][0]["digest"].as_str().unwrap().len(), 16);
+        assert!(json["conversions"][1].get("digest").is_none());
+
+        // A manifest is also a valid `--changelog-against` input
+        let report = read_manifest(&path).unwrap();
+        assert_eq!(report.success_count, 1);
+        assert_eq!(report.conversions[1].status, ConversionStatus::Error);
+    }
+}
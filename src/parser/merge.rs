@@ -0,0 +1,302 @@
+//! Combining several rules into one instruction file.
+
+use std::path::{Path, PathBuf};
+
+use super::batch::{finish_batch, keeps_target, print_kept, print_target, FileOutcome};
+use super::color::{eprint_colored, Color};
+use super::common::{find_cursor_files, write_target_with_parents, CursorMetadata};
+use super::options::{ConvertOptions, OutputFormat};
+use crate::error::{Result, RulerError};
+
+/// One rule's contribution to a merged instruction file.
+#[derive(Debug, Clone)]
+pub struct RuleSection {
+    /// Heading for the section: the rule's description, name or file stem.
+    pub title: String,
+    pub body: String,
+    pub priority: Option<i64>,
+}
+
+impl RuleSection {
+    /// The section for a Cursor rule, titled by the first line of its
+    /// description, else its name, else the file stem of `relative`.
+    pub fn from_rule(meta: &CursorMetadata, body: String, relative: &Path) -> RuleSection {
+        let title = meta
+            .description
+            .as_deref()
+            .and_then(|d| d.lines().next())
+            .filter(|d| !d.trim().is_empty())
+            .or(meta.name.as_deref())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                relative
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "rule".to_string())
+            });
+        RuleSection {
+            title,
+            body,
+            priority: meta.priority,
+        }
+    }
+}
+
+/// Joins `sections` into one Markdown body with a `## <title>` heading per
+/// rule, or with each rule in a collapsed `<details>` block titled by its
+/// `<summary>` when `collapsible` is set. Sections are ordered by descending
+/// `priority`, rules without one counting as 0; ties keep the order they were
+/// given in.
+pub fn merge_sections(sections: &[RuleSection], collapsible: bool) -> String {
+    let mut ordered: Vec<&RuleSection> = sections.iter().collect();
+    ordered.sort_by_key(|section| std::cmp::Reverse(section.priority.unwrap_or(0)));
+
+    ordered
+        .iter()
+        .map(|section| {
+            let body = section.body.trim();
+            if collapsible {
+                // The blank lines let Markdown inside the block render
+                format!(
+                    "<details>\n<summary>{}</summary>\n\n{}\n\n</details>\n",
+                    escape_summary(&section.title),
+                    body
+                )
+            } else {
+                format!("## {}\n\n{}\n", section.title, body)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Merges the Cursor rules in `from_dir` into the single file `target` with
+/// [`merge_sections`], then prints the summary. Rules are read in path order,
+/// so ties in `priority` keep that order.
+///
+/// `read_section` receives a rule's path and its path relative to `from_dir`
+/// and returns its section with any warnings, or `None` to leave the rule out
+/// of the file and the summary.
+pub fn merge_into_file<R>(
+    from_dir: &Path,
+    target: &Path,
+    options: &ConvertOptions,
+    read_section: R,
+) -> Result<()>
+where
+    R: Fn(&Path, &Path) -> Result<Option<(RuleSection, Vec<String>)>>,
+{
+    let source_files = find_cursor_files(from_dir, options)?;
+    merge_files_into_file(from_dir, &source_files, target, options, read_section)
+}
+
+/// Like [`merge_into_file`], for `source_files` under `from_dir` found by the
+/// caller, in the order given.
+pub fn merge_files_into_file<R>(
+    from_dir: &Path,
+    source_files: &[PathBuf],
+    target: &Path,
+    options: &ConvertOptions,
+    read_section: R,
+) -> Result<()>
+where
+    R: Fn(&Path, &Path) -> Result<Option<(RuleSection, Vec<String>)>>,
+{
+    let text_output = options.format == OutputFormat::Text;
+
+    let mut outcomes = Vec::new();
+    let mut sections = Vec::new();
+    let mut skipped = 0;
+    let mut aborted = false;
+    for (index, source_file) in source_files.iter().enumerate() {
+        let relative = source_file
+            .strip_prefix(from_dir)
+            .map_err(|_| RulerError::RelativePath(source_file.clone()))?;
+        let mut outcome = FileOutcome {
+            source: source_file.clone(),
+            relative: relative.to_path_buf(),
+            targets: vec![target.to_path_buf()],
+            error: None,
+            warnings: Vec::new(),
+            not_utf8: false,
+            unchanged: Vec::new(),
+            kept: false,
+            dropped: Vec::new(),
+        };
+
+        match read_section(source_file, relative) {
+            Ok(Some((section, warnings))) => {
+                sections.push(section);
+                outcome.warnings = warnings;
+            }
+            Ok(None) => continue,
+            Err(e) => {
+                if text_output {
+                    let line = format!("Error converting {}: {}", source_file.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
+                outcome.set_error(&e);
+            }
+        }
+        aborted = options.strict && outcome.has_problems();
+        outcomes.push(outcome);
+        if aborted {
+            skipped = source_files.len() - index - 1;
+            break;
+        }
+    }
+
+    // A file missing the sections of failed or skipped rules would replace a
+    // complete one, so the existing target is kept instead
+    let incomplete = aborted || outcomes.iter().any(|o| o.error.is_some());
+    // `--overwrite` keeps the target unless it would replace it for one of
+    // the merged rules
+    let kept = !outcomes.is_empty()
+        && outcomes
+            .iter()
+            .all(|o| keeps_target(&o.source, target, options));
+    if kept {
+        for outcome in &mut outcomes {
+            outcome.kept = true;
+        }
+    } else if incomplete {
+        if text_output {
+            let line = format!(
+                "Not writing {}: some rules were not merged",
+                target.display()
+            );
+            eprint_colored(options.color, Color::Red, &line);
+        }
+    } else {
+        match write_target_with_parents(target, &merge_sections(&sections, false), options) {
+            Ok(true) => {}
+            Ok(false) => {
+                for outcome in outcomes.iter_mut().filter(|o| o.error.is_none()) {
+                    outcome.unchanged.push(target.to_path_buf());
+                }
+            }
+            Err(e) => {
+                if text_output && options.check {
+                    eprint_colored(options.color, Color::Red, &e.to_string());
+                } else if text_output {
+                    let line = format!("Error writing {}: {}", target.display(), e);
+                    eprint_colored(options.color, Color::Red, &line);
+                }
+                for outcome in &mut outcomes {
+                    outcome.error.get_or_insert_with(|| e.to_string());
+                }
+            }
+        }
+    }
+
+    if text_output {
+        for outcome in outcomes.iter().filter(|o| o.error.is_none()) {
+            for warning in outcome
+                .warnings
+                .iter()
+                .filter(|_| !options.report_only_errors)
+            {
+                let line = format!("Warning: {}: {}", outcome.source.display(), warning);
+                eprint_colored(options.color, Color::Yellow, &line);
+            }
+            if options.check || options.quiet {
+                continue;
+            }
+            if kept {
+                print_kept(&outcome.source, target, options);
+            } else if !incomplete {
+                print_target(
+                    &outcome.source,
+                    target,
+                    !outcome.unchanged.is_empty(),
+                    options,
+                );
+            }
+        }
+    }
+
+    finish_batch(&outcomes, skipped, options)
+}
+
+fn escape_summary(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_into_file, merge_sections, RuleSection};
+    use crate::error::RulerError;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::io;
+
+    fn section(title: &str, priority: Option<i64>) -> RuleSection {
+        RuleSection {
+            title: title.to_string(),
+            body: format!("{} body.\n", title),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_merge_orders_by_descending_priority() {
+        let merged = merge_sections(
+            &[
+                section("Low", Some(-1)),
+                section("Unranked", None),
+                section("High", Some(10)),
+                section("Also unranked", None),
+            ],
+            false,
+        );
+        assert_eq!(
+            merged,
+            "## High\n\nHigh body.\n\n## Unranked\n\nUnranked body.\n\n## Also unranked\n\nAlso unranked body.\n\n## Low\n\nLow body.\n"
+        );
+    }
+
+    #[test]
+    fn test_collapsible_sections_wrap_each_rule_in_details() {
+        let merged = merge_sections(
+            &[section("Style", None), section("A <b> & c", Some(1))],
+            true,
+        );
+        assert_eq!(
+            merged,
+            "<details>\n<summary>A &lt;b&gt; &amp; c</summary>\n\nA <b> & c body.\n\n</details>\n\n<details>\n<summary>Style</summary>\n\nStyle body.\n\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn test_failed_rule_keeps_the_existing_target() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(from_dir.join("good.mdc"), "Good.").unwrap();
+        fs::write(from_dir.join("bad.mdc"), "Bad.").unwrap();
+        let target = temp.path().join("merged.md");
+        fs::write(&target, "## Complete\n\nEvery rule.\n").unwrap();
+
+        let result = merge_into_file(
+            &from_dir,
+            &target,
+            &ConvertOptions::default(),
+            |source_file, _| {
+                if source_file.ends_with("bad.mdc") {
+                    return Err(RulerError::Read {
+                        path: source_file.to_path_buf(),
+                        source: io::Error::other("unreadable"),
+                    });
+                }
+                Ok(Some((section("Good", None), Vec::new())))
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "## Complete\n\nEvery rule.\n"
+        );
+    }
+}
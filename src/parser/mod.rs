@@ -1,6 +1,53 @@
+pub mod archive;
+pub mod batch;
+pub mod bundle;
 pub mod c2g;
-pub mod g2c;
+pub mod c2w;
+pub mod changelog;
+pub mod claude;
+pub mod cline;
+pub mod color;
 pub mod common;
+pub mod copilot;
+pub mod coverage;
+pub mod defaults;
+pub mod env;
+pub mod g2c;
+pub mod include;
+pub mod language;
+pub mod lint;
+pub mod list;
+pub mod manifest;
+pub mod merge;
+pub mod options;
+pub mod registry;
+pub mod rename;
+pub mod scan;
+pub mod since;
+pub mod stats;
+pub mod upgrade;
+pub mod w2c;
+pub mod watch;
+pub mod zed;
 
-pub use c2g::convert_cursor_to_github;
-pub use g2c::convert_github_to_cursor;
+pub use bundle::{split_bundle_to_cursor, split_bundle_to_github};
+pub use c2g::{
+    convert_cursor_content, convert_cursor_content_with_options, convert_cursor_to_github,
+    convert_cursor_to_github_targets, split_cursor_content_by_glob, watch_cursor_to_github,
+};
+pub use c2w::{convert_cursor_content_to_windsurf, convert_cursor_to_windsurf};
+pub use claude::{convert_claude_content, convert_claude_to_cursor, convert_cursor_to_claude};
+pub use cline::{
+    convert_cline_content, convert_cline_to_cursor, convert_cursor_content_to_cline,
+    convert_cursor_to_cline,
+};
+pub use copilot::{merge_cursor_rules, split_copilot_instructions};
+pub use g2c::{
+    convert_github_content, convert_github_content_with_options, convert_github_to_cursor,
+};
+pub use options::{
+    ColorChoice, ConvertOptions, Eol, FrontmatterFormat, OutputFormat, OverwritePolicy,
+};
+pub use scan::{scan_cursor_dir, scan_github_dir};
+pub use w2c::{convert_windsurf_content, convert_windsurf_to_cursor};
+pub use zed::{convert_cursor_to_zed, convert_zed_content, convert_zed_to_cursor};
@@ -0,0 +1,248 @@
+use globset::Glob;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, RulerError};
+
+/// Default file name suffix of GitHub Copilot instructions.
+pub const INSTRUCTIONS_SUFFIX: &str = ".instructions.md";
+
+/// Default `applyTo` written for `alwaysApply: true` rules.
+pub const ALWAYS_APPLY_GLOB: &str = "**";
+
+/// How conversion progress and results are reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable progress lines
+    #[default]
+    Text,
+    /// TAP (Test Anything Protocol) stream, one test per source file
+    Tap,
+    /// A single JSON report printed once the run finishes
+    Json,
+}
+
+/// Frontmatter syntax, picked by the block's delimiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FrontmatterFormat {
+    /// YAML between `---` lines
+    #[default]
+    Yaml,
+    /// TOML between `+++` lines (Hugo/Zola style)
+    Toml,
+}
+
+impl FrontmatterFormat {
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            FrontmatterFormat::Yaml => "---",
+            FrontmatterFormat::Toml => "+++",
+        }
+    }
+}
+
+/// Whether a conversion may replace a target that already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OverwritePolicy {
+    /// Always write the converted target
+    #[default]
+    Always,
+    /// Keep existing targets; only missing ones are written
+    Never,
+    /// Replace a target only when its source was modified after it
+    IfNewer,
+}
+
+/// Line endings written to targets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Eol {
+    /// The source's own line endings
+    #[default]
+    Keep,
+    /// `\n` everywhere
+    Lf,
+    /// `\r\n` everywhere
+    Crlf,
+}
+
+impl Eol {
+    /// `text` with every line ending rewritten to this style.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Eol::Keep => text.to_string(),
+            Eol::Lf => text.replace("\r\n", "\n"),
+            Eol::Crlf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// When progress, warning and error lines are colored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color output written to a terminal
+    #[default]
+    Auto,
+    /// Always color, even when output is piped
+    Always,
+    /// Never color
+    Never,
+}
+
+/// Options shared by the directory-level conversions.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    pub format: OutputFormat,
+    /// Number of worker threads; `None` uses one per core.
+    pub jobs: Option<usize>,
+    /// c2g: write one instruction file per glob instead of joining globs into one `applyTo`.
+    pub one_glob_per_file: bool,
+    /// c2g: merge rules into one instruction file per language detected from their globs.
+    pub by_language: bool,
+    /// c2g `--by-language`: wrap each merged rule in a `<details>`/`<summary>` block.
+    pub collapsible_sections: bool,
+    /// g2c: concatenate every instruction into this one Markdown file instead
+    /// of writing a rule per instruction.
+    pub output_single: Option<PathBuf>,
+    /// After writing, fail if any source file is missing its target on disk.
+    pub verify_complete: bool,
+    /// Expand `@include <path>` lines in source bodies before converting.
+    pub expand_includes: bool,
+    /// Replace `${VAR}` in source bodies with the environment variable's value.
+    pub expand_env: bool,
+    /// Convert only the frontmatter and write each source's body byte for byte.
+    pub frontmatter_only: bool,
+    /// Walk every file under the source directory, ignoring `.gitignore` rules.
+    pub no_ignore: bool,
+    /// Follow symlinked files and directories while discovering sources.
+    pub follow_symlinks: bool,
+    /// Only discover sources at most this many levels below the source
+    /// directory; 1 keeps just the files directly in it.
+    pub max_depth: Option<usize>,
+    /// Write every target directly into the target directory instead of mirroring
+    /// the source's subdirectories.
+    pub flatten: bool,
+    /// Only convert sources whose path relative to the source directory matches one of these globs.
+    pub include: Vec<String>,
+    /// Skip sources whose path relative to the source directory matches any of these globs.
+    pub exclude: Vec<String>,
+    /// Only convert sources that git reports as changed since this ref.
+    pub since: Option<String>,
+    /// c2g: description template for rules without one; supports `{name}` and `{path}`.
+    pub default_description: Option<String>,
+    /// c2g: take a missing description from the body's first `# ` heading.
+    pub infer_description: bool,
+    /// Lowercase, deduplicate and sort `tags` in both directions.
+    pub normalize_tags: bool,
+    /// Frontmatter syntax to write; `None` keeps the syntax each source used.
+    pub frontmatter_format: Option<FrontmatterFormat>,
+    /// Fail a rule with a glob starting with `/` instead of only warning.
+    pub no_absolute_globs: bool,
+    /// Stop at the first source that fails or warns and exit with an error; a
+    /// glob that doesn't compile fails its rule instead of only warning.
+    pub strict: bool,
+    /// Exit with an error once the run finishes if any warning was emitted.
+    pub fail_on_warning: bool,
+    /// After the run, print a Markdown changelog against this saved `--format json` report.
+    pub changelog_against: Option<PathBuf>,
+    /// Write a JSON manifest of every source, target, status and digest here after the run.
+    pub manifest: Option<PathBuf>,
+    /// Name of the conversion mode, e.g. `c2g`, recorded in the `--manifest`.
+    pub mode: Option<String>,
+    /// upgrade-globs: list the files that would change instead of rewriting them.
+    pub dry_run: bool,
+    /// Copy a target to `<target>.bak` before overwriting it with different contents.
+    pub backup: bool,
+    /// Whether existing targets may be replaced.
+    pub overwrite: OverwritePolicy,
+    /// After converting, delete the targets the previous `--manifest` recorded
+    /// for sources that are gone or now produce other targets.
+    pub prune: bool,
+    /// Convert in memory and compare with the existing targets instead of writing.
+    pub check: bool,
+    /// With `check`, print a unified diff of each target that would change
+    /// instead of failing on it.
+    pub diff: bool,
+    /// Suppress progress lines; the summary, warnings and errors are still reported.
+    pub quiet: bool,
+    /// Like `quiet`, also suppressing warnings and the notes after the summary,
+    /// which is followed by a list of every source that failed.
+    pub report_only_errors: bool,
+    /// c2g/g2c: file name suffix of GitHub instructions instead of `.instructions.md`.
+    pub target_extension: Option<String>,
+    /// c2g/g2c: `applyTo` pattern meaning "every file" instead of `**`.
+    pub always_apply_glob: Option<String>,
+    /// c2g/g2c: target file names keyed by source path relative to the source
+    /// directory, overriding the names derived from the source's stem.
+    pub rename_map: BTreeMap<PathBuf, String>,
+    /// c2g: layout of every generated instruction, in which `{{frontmatter}}`
+    /// and `{{body}}` are replaced after the frontmatter is serialized.
+    pub template: Option<String>,
+    /// c2g: write no frontmatter; the rule's name and description open the
+    /// body as a `#` heading and a paragraph instead.
+    pub no_frontmatter: bool,
+    /// When to color progress, warning and error lines.
+    pub color: ColorChoice,
+    /// Line endings of the written targets.
+    pub eol: Eol,
+}
+
+impl ConvertOptions {
+    /// Whether human-readable progress lines should be printed.
+    pub fn shows_progress(&self) -> bool {
+        self.format == OutputFormat::Text && !self.quiet
+    }
+
+    /// The file name suffix of GitHub instructions: `target_extension`, or
+    /// `.instructions.md` when it isn't set.
+    pub fn instructions_suffix(&self) -> &str {
+        self.target_extension
+            .as_deref()
+            .unwrap_or(INSTRUCTIONS_SUFFIX)
+    }
+
+    /// The `applyTo` pattern written for rules that always apply:
+    /// `always_apply_glob`, or `**` when it isn't set.
+    pub fn always_apply_glob(&self) -> &str {
+        self.always_apply_glob
+            .as_deref()
+            .unwrap_or(ALWAYS_APPLY_GLOB)
+    }
+
+    /// The relative target path for `relative_path` when `rename_map` names
+    /// it: the mapped file name in the source's subdirectory.
+    pub fn renamed_target(&self, relative_path: &Path) -> Option<PathBuf> {
+        self.rename_map
+            .get(relative_path)
+            .map(|name| relative_path.with_file_name(name))
+    }
+
+    /// Fails if `target_extension` is not a suffix such as `.md`: it must start
+    /// with a dot and can't contain a path separator.
+    pub fn validate_target_extension(&self) -> Result<()> {
+        match &self.target_extension {
+            Some(suffix)
+                if !suffix.starts_with('.') || suffix.len() < 2 || suffix.contains(['/', '\\']) =>
+            {
+                Err(RulerError::TargetExtension(suffix.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `always_apply_glob`, when set, is a pattern that compiles.
+    pub fn validate_always_apply_glob(&self) -> Result<()> {
+        let Some(glob) = &self.always_apply_glob else {
+            return Ok(());
+        };
+        if glob.trim().is_empty() {
+            return Err(RulerError::EmptyAlwaysApplyGlob);
+        }
+        match Glob::new(glob) {
+            Ok(_) => Ok(()),
+            Err(source) => Err(RulerError::InvalidGlob {
+                glob: glob.clone(),
+                source,
+            }),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! The conversion modes, each described by a [`Converter`].
+//!
+//! `main` looks a mode up by name with [`find_converter`] and resolves its
+//! source and targets from the defaults here, so adding a format means
+//! implementing [`Converter`] and listing it in [`converters`].
+
+use std::path::{Path, PathBuf};
+
+use super::claude::CLAUDE_FILE;
+use super::copilot::COPILOT_INSTRUCTIONS;
+use super::options::ConvertOptions;
+use super::zed::ZED_FILE;
+use super::{
+    convert_claude_content, convert_claude_to_cursor, convert_cline_content,
+    convert_cline_to_cursor, convert_cursor_content_to_cline, convert_cursor_content_to_windsurf,
+    convert_cursor_content_with_options, convert_cursor_to_claude, convert_cursor_to_cline,
+    convert_cursor_to_github, convert_cursor_to_github_targets, convert_cursor_to_windsurf,
+    convert_cursor_to_zed, convert_github_content_with_options, convert_github_to_cursor,
+    convert_windsurf_content, convert_windsurf_to_cursor, convert_zed_content,
+    convert_zed_to_cursor, merge_cursor_rules, split_bundle_to_cursor, split_bundle_to_github,
+    split_copilot_instructions,
+};
+use crate::error::Result;
+
+/// Default directory of Cursor rules.
+pub const CURSOR_RULES_DIR: &str = ".cursor/rules";
+
+/// A conversion mode: where it reads from and writes to by default, and how it
+/// converts a whole tree and, if it can, a single file's contents.
+pub trait Converter: Sync {
+    /// The mode's name on the command line, e.g. `c2g`.
+    fn name(&self) -> &'static str;
+
+    /// The source used without `--from`, or `None` if it must be given.
+    fn default_from(&self) -> Option<&'static str>;
+
+    /// The target used without `--to`.
+    fn default_to(&self) -> &'static str;
+
+    /// Whether the source is a directory of rules, which may also be given as
+    /// an archive, rather than a single file.
+    fn reads_dir(&self) -> bool;
+
+    /// Whether the source is a Cursor rules directory, which is looked for
+    /// under the alternate rule roots when `.cursor/rules` is missing.
+    fn reads_cursor_rules(&self) -> bool {
+        self.default_from() == Some(CURSOR_RULES_DIR)
+    }
+
+    /// Whether `--to` may be given more than once.
+    fn multiple_targets(&self) -> bool {
+        false
+    }
+
+    /// Converts the source `from` into the target `to`, either of which may be
+    /// a directory or a single file depending on the mode.
+    fn convert_dir(&self, from: &Path, to: &Path, options: &ConvertOptions) -> Result<()>;
+
+    /// Converts `from` into each of `to`, which holds a single target unless
+    /// [`Converter::multiple_targets`] is true.
+    fn convert_targets(&self, from: &Path, to: &[PathBuf], options: &ConvertOptions) -> Result<()> {
+        to.iter()
+            .try_for_each(|to| self.convert_dir(from, to, options))
+    }
+
+    /// Converts a single file's contents, for `--stdin`. `None` if the mode
+    /// only works on whole trees.
+    fn convert_content(&self, _content: &str, _options: &ConvertOptions) -> Option<Result<String>> {
+        None
+    }
+}
+
+type ConvertDir = fn(&Path, &Path, &ConvertOptions) -> Result<()>;
+type ConvertContent = fn(&str, &ConvertOptions) -> Result<String>;
+
+// A mode that is fully described by its defaults and conversion functions.
+struct Builtin {
+    name: &'static str,
+    from: Option<&'static str>,
+    reads_dir: bool,
+    to: &'static str,
+    convert: ConvertDir,
+    content: Option<ConvertContent>,
+}
+
+impl Converter for Builtin {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn default_from(&self) -> Option<&'static str> {
+        self.from
+    }
+
+    fn reads_dir(&self) -> bool {
+        self.reads_dir
+    }
+
+    fn default_to(&self) -> &'static str {
+        self.to
+    }
+
+    fn convert_dir(&self, from: &Path, to: &Path, options: &ConvertOptions) -> Result<()> {
+        (self.convert)(from, to, options)
+    }
+
+    fn convert_content(&self, content: &str, options: &ConvertOptions) -> Option<Result<String>> {
+        self.content.map(|convert| convert(content, options))
+    }
+}
+
+// `c2g`, the one mode that writes to several `--to` directories at once.
+struct CursorToGithub;
+
+impl Converter for CursorToGithub {
+    fn name(&self) -> &'static str {
+        "c2g"
+    }
+
+    fn default_from(&self) -> Option<&'static str> {
+        Some(CURSOR_RULES_DIR)
+    }
+
+    fn reads_dir(&self) -> bool {
+        true
+    }
+
+    fn default_to(&self) -> &'static str {
+        ".github/instructions"
+    }
+
+    fn multiple_targets(&self) -> bool {
+        true
+    }
+
+    fn convert_dir(&self, from: &Path, to: &Path, options: &ConvertOptions) -> Result<()> {
+        convert_cursor_to_github(from, to, options)
+    }
+
+    fn convert_targets(&self, from: &Path, to: &[PathBuf], options: &ConvertOptions) -> Result<()> {
+        convert_cursor_to_github_targets(from, to, options)
+    }
+
+    fn convert_content(&self, content: &str, options: &ConvertOptions) -> Option<Result<String>> {
+        Some(convert_cursor_content_with_options(content, options, None))
+    }
+}
+
+static CONVERTERS: [&dyn Converter; 14] = [
+    &CursorToGithub,
+    &Builtin {
+        name: "g2c",
+        from: Some(".github/instructions"),
+        reads_dir: true,
+        to: CURSOR_RULES_DIR,
+        convert: convert_github_to_cursor,
+        content: Some(convert_github_content_with_options),
+    },
+    &Builtin {
+        name: "c2w",
+        from: Some(CURSOR_RULES_DIR),
+        reads_dir: true,
+        to: ".windsurf/rules",
+        convert: convert_cursor_to_windsurf,
+        content: Some(convert_cursor_content_to_windsurf),
+    },
+    &Builtin {
+        name: "w2c",
+        from: Some(".windsurf/rules"),
+        reads_dir: true,
+        to: CURSOR_RULES_DIR,
+        convert: convert_windsurf_to_cursor,
+        content: Some(convert_windsurf_content),
+    },
+    &Builtin {
+        name: "c2cl",
+        from: Some(CURSOR_RULES_DIR),
+        reads_dir: true,
+        to: ".clinerules",
+        convert: convert_cursor_to_cline,
+        content: Some(convert_cursor_content_to_cline),
+    },
+    &Builtin {
+        name: "cl2c",
+        from: Some(".clinerules"),
+        reads_dir: true,
+        to: CURSOR_RULES_DIR,
+        convert: convert_cline_to_cursor,
+        content: Some(convert_cline_content),
+    },
+    &Builtin {
+        name: "c2g-merge",
+        from: Some(CURSOR_RULES_DIR),
+        reads_dir: true,
+        to: COPILOT_INSTRUCTIONS,
+        convert: merge_cursor_rules,
+        content: None,
+    },
+    &Builtin {
+        name: "g2c-split",
+        from: Some(COPILOT_INSTRUCTIONS),
+        reads_dir: false,
+        to: CURSOR_RULES_DIR,
+        convert: split_copilot_instructions,
+        content: None,
+    },
+    &Builtin {
+        name: "bundle2c",
+        from: None,
+        reads_dir: false,
+        to: CURSOR_RULES_DIR,
+        convert: split_bundle_to_cursor,
+        content: None,
+    },
+    &Builtin {
+        name: "bundle2g",
+        from: None,
+        reads_dir: false,
+        to: ".github/instructions",
+        convert: split_bundle_to_github,
+        content: None,
+    },
+    &Builtin {
+        name: "c2claude",
+        from: Some(CURSOR_RULES_DIR),
+        reads_dir: true,
+        to: CLAUDE_FILE,
+        convert: convert_cursor_to_claude,
+        content: None,
+    },
+    &Builtin {
+        name: "claude2c",
+        from: Some(CLAUDE_FILE),
+        reads_dir: false,
+        to: CURSOR_RULES_DIR,
+        convert: convert_claude_to_cursor,
+        content: Some(|content, _| convert_claude_content(content)),
+    },
+    &Builtin {
+        name: "c2zed",
+        from: Some(CURSOR_RULES_DIR),
+        reads_dir: true,
+        to: ZED_FILE,
+        convert: convert_cursor_to_zed,
+        content: None,
+    },
+    &Builtin {
+        name: "zed2c",
+        from: Some(ZED_FILE),
+        reads_dir: false,
+        to: CURSOR_RULES_DIR,
+        convert: convert_zed_to_cursor,
+        content: Some(|content, _| convert_zed_content(content)),
+    },
+];
+
+/// Every built-in conversion mode, in the order they are documented.
+pub fn converters() -> &'static [&'static dyn Converter] {
+    &CONVERTERS
+}
+
+/// The conversion mode called `name` on the command line, if any. Modes that
+/// don't convert, like `list`, have no converter.
+pub fn find_converter(name: &str) -> Option<&'static dyn Converter> {
+    converters()
+        .iter()
+        .copied()
+        .find(|converter| converter.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{converters, find_converter, CURSOR_RULES_DIR};
+
+    #[test]
+    fn test_registry_lists_builtin_converters() {
+        let names: Vec<&str> = converters().iter().map(|c| c.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "c2g",
+                "g2c",
+                "c2w",
+                "w2c",
+                "c2cl",
+                "cl2c",
+                "c2g-merge",
+                "g2c-split",
+                "bundle2c",
+                "bundle2g",
+                "c2claude",
+                "claude2c",
+                "c2zed",
+                "zed2c",
+            ]
+        );
+
+        let c2g = find_converter("c2g").unwrap();
+        assert!(c2g.reads_cursor_rules() && c2g.multiple_targets());
+        assert_eq!(c2g.default_to(), ".github/instructions");
+        let g2c = find_converter("g2c").unwrap();
+        assert!(!g2c.reads_cursor_rules() && !g2c.multiple_targets());
+        assert_eq!(g2c.default_from(), Some(".github/instructions"));
+        assert_eq!(g2c.default_to(), CURSOR_RULES_DIR);
+        let bundle2c = find_converter("bundle2c").unwrap();
+        assert!(bundle2c.default_from().is_none() && !bundle2c.reads_dir());
+        assert!(find_converter("c2claude")
+            .unwrap()
+            .convert_content("", &Default::default())
+            .is_none());
+        assert!(find_converter("list").is_none());
+    }
+}
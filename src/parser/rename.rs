@@ -0,0 +1,101 @@
+//! `--rename-map`: target file names chosen per source instead of derived
+//! from the source's stem.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Result, RulerError};
+
+/// Reads a rename map: a JSON object when `path` ends in `.json`, otherwise
+/// tab-separated `source<TAB>target` lines where blank lines and lines
+/// starting with `#` are skipped. Sources are paths relative to the source
+/// directory; targets are bare file names.
+pub fn read_rename_map(path: &Path) -> Result<BTreeMap<PathBuf, String>> {
+    let content = fs::read_to_string(path).map_err(|source| RulerError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let invalid = |message: String| RulerError::RenameMap {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let entries: Vec<(String, String)> = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str::<BTreeMap<String, String>>(&content)
+            .map_err(|e| invalid(e.to_string()))?
+            .into_iter()
+            .collect()
+    } else {
+        let mut entries = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('\t') {
+                Some((source, target)) => {
+                    entries.push((source.trim().to_string(), target.trim().to_string()))
+                }
+                None => {
+                    return Err(invalid(format!(
+                        "line {}: expected `source<TAB>target`",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        entries
+    };
+
+    let mut map = BTreeMap::new();
+    for (source, target) in entries {
+        if target.is_empty() || target.contains(['/', '\\']) {
+            return Err(invalid(format!(
+                "`{}` must map to a file name, not `{}`",
+                source, target
+            )));
+        }
+        // `./style.mdc` and `style.mdc` name the same source
+        let source = Path::new(&source)
+            .components()
+            .filter(|component| component != &Component::CurDir)
+            .collect();
+        map.insert(source, target);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_rename_map;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_read_tsv_and_json_rename_maps() {
+        let temp = tempfile::tempdir().unwrap();
+        let tsv = temp.path().join("names.tsv");
+        fs::write(
+            &tsv,
+            "# source\ttarget\n./style.mdc\tcode-style.instructions.md\n\nlang/rust.mdc\trust.md\n",
+        )
+        .unwrap();
+        let map = read_rename_map(&tsv).unwrap();
+        assert_eq!(
+            map[&PathBuf::from("style.mdc")],
+            "code-style.instructions.md"
+        );
+        assert_eq!(map[&PathBuf::from("lang/rust.mdc")], "rust.md");
+
+        let json = temp.path().join("names.json");
+        fs::write(&json, r#"{"style.mdc": "code-style.instructions.md"}"#).unwrap();
+        assert_eq!(read_rename_map(&json).unwrap().len(), 1);
+
+        fs::write(&tsv, "style.mdc code-style.md\n").unwrap();
+        let err = read_rename_map(&tsv).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+        fs::write(&json, r#"{"style.mdc": "nested/style.md"}"#).unwrap();
+        assert!(read_rename_map(&json).is_err());
+    }
+}
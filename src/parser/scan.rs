@@ -0,0 +1,155 @@
+//! Reading a directory of rules without converting or writing anything.
+
+use std::path::{Path, PathBuf};
+
+use super::common::{
+    deserialize_frontmatter, find_cursor_files, find_github_files, parse_cursor_metadata,
+    parse_frontmatter_with_field_info, read_source_file, CursorMetadata, GithubMetadata,
+};
+use super::defaults::read_cursor_rule;
+use super::options::{ConvertOptions, FrontmatterFormat};
+use crate::error::{Result, RulerError};
+
+/// Every Cursor rule in `dir` as `(path, metadata, body)`, in path order. A
+/// rule without frontmatter gets default metadata.
+pub fn scan_cursor_dir(dir: &Path) -> Result<Vec<(PathBuf, CursorMetadata, String)>> {
+    scan_cursor_dir_with_options(dir, &ConvertOptions::default())
+}
+
+/// Like [`scan_cursor_dir`], discovering and reading the rules as a
+/// conversion with `options` would: `--include`/`--exclude`, ignore files,
+/// symlinks, `@include` expansion and `_defaults.mdc` inheritance apply.
+pub fn scan_cursor_dir_with_options(
+    dir: &Path,
+    options: &ConvertOptions,
+) -> Result<Vec<(PathBuf, CursorMetadata, String)>> {
+    let scanned = scan_cursor_frontmatter(dir, options)?;
+    Ok(with_default_metadata(scanned))
+}
+
+/// Like [`scan_cursor_dir_with_options`], with `None` rather than default
+/// metadata for a rule without frontmatter.
+pub fn scan_cursor_frontmatter(
+    dir: &Path,
+    options: &ConvertOptions,
+) -> Result<Vec<(PathBuf, Option<CursorMetadata>, String)>> {
+    scan(
+        find_cursor_files(dir, options)?,
+        options,
+        read_cursor_rule,
+        parse_cursor_metadata,
+    )
+}
+
+/// Every GitHub Copilot instruction in `dir` as `(path, metadata, body)`, in
+/// path order. An instruction without frontmatter gets default metadata.
+pub fn scan_github_dir(dir: &Path) -> Result<Vec<(PathBuf, GithubMetadata, String)>> {
+    scan_github_dir_with_options(dir, &ConvertOptions::default())
+}
+
+/// Like [`scan_github_dir`], discovering and reading the instructions as a
+/// conversion with `options` would.
+pub fn scan_github_dir_with_options(
+    dir: &Path,
+    options: &ConvertOptions,
+) -> Result<Vec<(PathBuf, GithubMetadata, String)>> {
+    let scanned = scan(
+        find_github_files(dir, options)?,
+        options,
+        read_source_file,
+        |frontmatter, format| deserialize_frontmatter(frontmatter, format, "GitHub"),
+    )?;
+    Ok(with_default_metadata(scanned))
+}
+
+fn with_default_metadata<M: Default>(
+    scanned: Vec<(PathBuf, Option<M>, String)>,
+) -> Vec<(PathBuf, M, String)> {
+    scanned
+        .into_iter()
+        .map(|(path, meta, body)| (path, meta.unwrap_or_default(), body))
+        .collect()
+}
+
+// Reads each of `files` with `read` and parses it, with `None` metadata for a
+// file without frontmatter; the first file that fails stops the scan with an
+// error naming it.
+fn scan<M, P>(
+    files: Vec<PathBuf>,
+    options: &ConvertOptions,
+    read: fn(&Path, &ConvertOptions) -> Result<String>,
+    parse: P,
+) -> Result<Vec<(PathBuf, Option<M>, String)>>
+where
+    P: Fn(&str, FrontmatterFormat) -> Result<M>,
+{
+    files
+        .into_iter()
+        .map(|path| {
+            let read_rule = || -> Result<(Option<M>, String)> {
+                let content = read(&path, options)?;
+                let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(&content)?;
+                let meta = match frontmatter {
+                    Some(fm) => Some(parse(&fm, field_info.format)?),
+                    None => None,
+                };
+                Ok((meta, body))
+            };
+            match read_rule() {
+                Ok((meta, body)) => Ok((path, meta, body)),
+                Err(e) => Err(RulerError::Rule {
+                    path,
+                    source: Box::new(e),
+                }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan_cursor_dir, scan_github_dir};
+    use std::fs;
+
+    #[test]
+    fn test_scan_returns_metadata_and_body_without_writing() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path().join("rules");
+        let instructions = temp.path().join("instructions");
+        fs::create_dir_all(rules.join("lang")).unwrap();
+        fs::create_dir_all(&instructions).unwrap();
+        fs::write(
+            rules.join("lang/rust.mdc"),
+            "---\ndescription: Rust\nglobs: \"*.rs\"\n---\n\nUse clippy.",
+        )
+        .unwrap();
+        fs::write(rules.join("plain.md"), "Just a body.").unwrap();
+        fs::write(
+            instructions.join("rust.instructions.md"),
+            "---\napplyTo: \"*.rs\"\n---\n\nUse clippy.",
+        )
+        .unwrap();
+
+        let scanned = scan_cursor_dir(&rules).unwrap();
+        assert_eq!(scanned.len(), 2);
+        let (path, meta, body) = &scanned[0];
+        assert_eq!(path, &rules.join("lang/rust.mdc"));
+        assert_eq!(meta.description.as_deref(), Some("Rust"));
+        assert_eq!(meta.globs, Some(vec!["*.rs".to_string()]));
+        assert_eq!(body, "Use clippy.");
+        assert_eq!(scanned[1].1.description, None);
+        assert_eq!(scanned[1].2, "Just a body.");
+
+        let scanned = scan_github_dir(&instructions).unwrap();
+        assert_eq!(scanned[0].1.apply_to.as_deref(), Some("*.rs"));
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 2);
+
+        fs::write(
+            rules.join("broken.mdc"),
+            "---\nglobs: [unclosed\n---\n\nBody.",
+        )
+        .unwrap();
+        let err = scan_cursor_dir(&rules).unwrap_err();
+        assert!(err.to_string().contains("broken.mdc"));
+    }
+}
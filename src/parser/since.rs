@@ -0,0 +1,173 @@
+//! `--since <REF>`: limiting a run to the sources changed since a git ref.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Result, RulerError};
+
+/// The files under `dir` that differ from `reference` in git, relative to
+/// `dir`: committed and uncommitted changes to tracked files, plus untracked
+/// files that aren't ignored. Runs the `git` executable found on `PATH`.
+pub fn changed_since(dir: &Path, reference: &str) -> Result<HashSet<PathBuf>> {
+    // git would read it as an option, e.g. `--output=<file>`
+    if reference.starts_with('-') {
+        return Err(RulerError::Git {
+            reference: reference.to_string(),
+            message: "a ref can't start with `-`".to_string(),
+        });
+    }
+    let mut changed = git_paths(
+        dir,
+        reference,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "-z",
+            reference,
+            "--",
+            ".",
+        ],
+    )?;
+    changed.extend(git_paths(
+        dir,
+        reference,
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "-z",
+            "--",
+            ".",
+        ],
+    )?);
+    Ok(changed)
+}
+
+// Runs `git` in `dir` and reads its NUL-separated list of paths.
+fn git_paths(dir: &Path, reference: &str, args: &[&str]) -> Result<HashSet<PathBuf>> {
+    let git_error = |message: String| RulerError::Git {
+        reference: reference.to_string(),
+        message,
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| git_error(format!("could not run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(git_error(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_since;
+    use crate::parser::common::find_cursor_files;
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=ruler",
+                "-c",
+                "user.email=ruler@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_changed_since_lists_committed_modified_and_new_rules() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path();
+        let rules = repo.join(".cursor/rules");
+        fs::create_dir_all(&rules).unwrap();
+        for rule in ["old.mdc", "edited.mdc", "committed.mdc"] {
+            fs::write(rules.join(rule), "Before.").unwrap();
+        }
+        fs::write(repo.join("README.md"), "Outside the rules.").unwrap();
+        git(repo, &["init", "-q"]);
+        git(repo, &["add", "-A"]);
+        git(repo, &["commit", "-q", "-m", "base"]);
+        git(repo, &["tag", "base"]);
+
+        fs::write(rules.join("committed.mdc"), "After.").unwrap();
+        fs::write(repo.join("README.md"), "Changed, but not a rule.").unwrap();
+        git(repo, &["commit", "-q", "-am", "change"]);
+        fs::write(rules.join("edited.mdc"), "Uncommitted.").unwrap();
+        fs::write(rules.join("new.mdc"), "Untracked.").unwrap();
+
+        let mut changed: Vec<PathBuf> =
+            changed_since(&rules, "base").unwrap().into_iter().collect();
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("committed.mdc"),
+                PathBuf::from("edited.mdc"),
+                PathBuf::from("new.mdc")
+            ]
+        );
+
+        let err = changed_since(&rules, "no-such-ref").unwrap_err();
+        assert!(err.to_string().contains("`no-such-ref`"));
+
+        let output = repo.join("written.txt");
+        let option = format!("--output={}", output.display());
+        assert!(changed_since(&rules, &option).is_err());
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_changed_defaults_select_their_siblings() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path();
+        let rules = repo.join(".cursor/rules");
+        fs::create_dir_all(rules.join("api/deep")).unwrap();
+        for rule in [
+            "top.mdc",
+            "api/_defaults.mdc",
+            "api/handler.mdc",
+            "api/deep/nested.mdc",
+        ] {
+            fs::write(rules.join(rule), "---\ndescription: Before\n---\n").unwrap();
+        }
+        git(repo, &["init", "-q"]);
+        git(repo, &["add", "-A"]);
+        git(repo, &["commit", "-q", "-m", "base"]);
+
+        fs::write(
+            rules.join("api/_defaults.mdc"),
+            "---\ndescription: After\n---\n",
+        )
+        .unwrap();
+        let options = ConvertOptions {
+            since: Some("HEAD".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            find_cursor_files(&rules, &options).unwrap(),
+            vec![rules.join("api/handler.mdc")]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! `ruler stats`: how a rule set breaks down by category, counted without
+//! converting anything.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::options::ConvertOptions;
+use super::scan::scan_cursor_frontmatter;
+use crate::error::Result;
+
+/// Counts of the Cursor rules in a directory. A rule can fall into several
+/// categories, e.g. an always-applied rule with a description.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RuleStats {
+    /// Every rule file found.
+    pub total: usize,
+    /// `alwaysApply: true`.
+    pub always_applied: usize,
+    /// Not always applied, with at least one glob.
+    pub glob_scoped: usize,
+    /// A non-empty `description`.
+    pub with_description: usize,
+    /// No frontmatter block at all.
+    pub no_frontmatter: usize,
+}
+
+/// Counts the Cursor rules in `rules_dir`, discovered and read as a conversion
+/// with `options` would. A rule whose frontmatter can't be parsed fails the
+/// whole count.
+pub fn compute_stats(rules_dir: &Path, options: &ConvertOptions) -> Result<RuleStats> {
+    let mut stats = RuleStats::default();
+    for (_, meta, _) in scan_cursor_frontmatter(rules_dir, options)? {
+        stats.total += 1;
+        let Some(meta) = meta else {
+            stats.no_frontmatter += 1;
+            continue;
+        };
+        if meta.always_apply == Some(true) {
+            stats.always_applied += 1;
+        } else if meta.globs.is_some_and(|globs| !globs.is_empty()) {
+            stats.glob_scoped += 1;
+        }
+        if meta.description.is_some_and(|d| !d.trim().is_empty()) {
+            stats.with_description += 1;
+        }
+    }
+    Ok(stats)
+}
+
+/// Renders the counts printed by `ruler stats`, one category per line.
+pub fn render_stats(stats: &RuleStats) -> String {
+    format!(
+        "Rules:            {}\n\
+         Always applied:   {}\n\
+         Glob-scoped:      {}\n\
+         With description: {}\n\
+         No frontmatter:   {}\n",
+        stats.total,
+        stats.always_applied,
+        stats.glob_scoped,
+        stats.with_description,
+        stats.no_frontmatter
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_stats, render_stats, RuleStats};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+
+    #[test]
+    fn test_stats_count_each_category() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path();
+        fs::create_dir_all(rules.join("lang")).unwrap();
+        fs::write(
+            rules.join("style.mdc"),
+            "---\ndescription: House style\nalwaysApply: true\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(
+            rules.join("lang/rust.mdc"),
+            "---\ndescription: Rust\nglobs: \"*.rs\"\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(
+            rules.join("lang/go.mdc"),
+            "---\nglobs: [\"*.go\"]\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(
+            rules.join("manual.mdc"),
+            "---\nalwaysApply: false\n---\n\nBody.",
+        )
+        .unwrap();
+        fs::write(rules.join("snippets.md"), "Body.").unwrap();
+
+        let stats = compute_stats(rules, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            stats,
+            RuleStats {
+                total: 5,
+                always_applied: 1,
+                glob_scoped: 2,
+                with_description: 2,
+                no_frontmatter: 1,
+            }
+        );
+        assert_eq!(
+            render_stats(&stats),
+            "Rules:            5\nAlways applied:   1\nGlob-scoped:      2\nWith description: 2\nNo frontmatter:   1\n"
+        );
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["glob_scoped"], 2);
+    }
+}
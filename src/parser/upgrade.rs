@@ -0,0 +1,148 @@
+//! `upgrade-globs`: rewrites the legacy comma-string `globs` form in Cursor
+//! rules as a proper YAML array, in place.
+
+use std::path::Path;
+
+use super::batch::{run_batch, Converted};
+use super::common::{
+    find_cursor_files, preprocess_frontmatter, read_source_file, strip_bom, write_target,
+};
+use super::options::{ConvertOptions, OutputFormat};
+use crate::error::Result;
+
+/// Rewrites every Cursor rule under `dir` whose `globs` is a comma-separated
+/// string (`globs: "a,b"` or `globs: "a", "b"`) to `globs: ["a", "b"]`.
+/// Nothing is written with `options.dry_run`; the files that would change are
+/// listed instead.
+pub fn upgrade_globs(dir: &Path, options: &ConvertOptions) -> Result<()> {
+    // Rules are rewritten where they are
+    let options = &ConvertOptions {
+        flatten: false,
+        ..options.clone()
+    };
+    if options.shows_progress() {
+        println!("Upgrading legacy comma-separated globs...");
+        println!("In: {}", dir.display());
+    }
+
+    let source_files = find_cursor_files(dir, options)?;
+
+    if source_files.is_empty() && options.shows_progress() {
+        println!("No .mdc or .md files found in {}", dir.display());
+    }
+
+    run_batch(
+        dir,
+        dir,
+        None,
+        source_files,
+        options,
+        |relative_path| relative_path.to_path_buf(),
+        |source_file, _relative_path, _target_path| {
+            // Upgrade the file as written, without expanding includes or
+            // environment variables
+            let options = &ConvertOptions {
+                expand_includes: false,
+                expand_env: false,
+                ..options.clone()
+            };
+            let content = read_source_file(source_file, options)?;
+            let upgraded = match upgrade_globs_content(&content) {
+                Some(upgraded) => upgraded,
+                None => return Ok(Converted::default()),
+            };
+            if options.dry_run {
+                if options.format == OutputFormat::Text {
+                    println!("Would upgrade: {}", source_file.display());
+                }
+                return Ok(Converted::default());
+            }
+            write_target(source_file, &upgraded, options)?;
+            Ok(vec![source_file.to_path_buf()].into())
+        },
+    )
+}
+
+/// Rewrites a comma-string `globs` line in the YAML frontmatter of `content`
+/// as an array, leaving every other line byte-for-byte unchanged. Returns
+/// `None` when there is nothing to upgrade.
+pub fn upgrade_globs_content(content: &str) -> Option<String> {
+    let mut lines = content.split_inclusive('\n');
+    let opening = lines.next()?;
+    // A byte-order mark is kept, since the file is rewritten in place
+    if strip_bom(opening).trim_end() != "---" {
+        return None;
+    }
+
+    let mut upgraded = opening.to_string();
+    let mut changed = false;
+    let mut in_frontmatter = true;
+    for line in lines {
+        let text = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[text.len()..];
+        if in_frontmatter && text.trim() == "---" {
+            in_frontmatter = false;
+        } else if in_frontmatter && text.starts_with("globs:") {
+            let rewritten = preprocess_frontmatter(text);
+            let rewritten = rewritten.trim_end_matches('\n');
+            if rewritten != text {
+                upgraded.push_str(rewritten);
+                upgraded.push_str(ending);
+                changed = true;
+                continue;
+            }
+        }
+        upgraded.push_str(line);
+    }
+
+    if in_frontmatter || !changed {
+        return None;
+    }
+    Some(upgraded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{upgrade_globs, upgrade_globs_content};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+
+    #[test]
+    fn test_upgrade_globs_rewrites_comma_string_in_place() {
+        let temp = tempfile::tempdir().unwrap();
+        let legacy =
+            "---\r\ndescription: \"Legacy\"\r\nglobs: \"*.ts,src/**/*.tsx\"\r\n---\r\n\r\nBody.";
+        fs::write(temp.path().join("legacy.mdc"), legacy).unwrap();
+        let modern = "---\nglobs: [\"*.rs\"]\n---\n\nBody.";
+        fs::write(temp.path().join("modern.mdc"), modern).unwrap();
+
+        let dry_run = ConvertOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        upgrade_globs(temp.path(), &dry_run).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("legacy.mdc")).unwrap(),
+            legacy
+        );
+
+        upgrade_globs(temp.path(), &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("legacy.mdc")).unwrap(),
+            "---\r\ndescription: \"Legacy\"\r\nglobs: [\"*.ts\", \"src/**/*.tsx\"]\r\n---\r\n\r\nBody.\r\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("modern.mdc")).unwrap(),
+            modern
+        );
+    }
+
+    #[test]
+    fn test_upgrade_multiple_quoted_strings() {
+        assert_eq!(
+            upgrade_globs_content("---\nglobs: \"a/**\", \"b/**\"\n---\nBody").as_deref(),
+            Some("---\nglobs: [\"a/**\", \"b/**\"]\n---\nBody")
+        );
+        assert_eq!(upgrade_globs_content("No frontmatter, globs: a,b"), None);
+    }
+}
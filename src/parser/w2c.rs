@@ -0,0 +1,140 @@
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+use super::batch::{run_batch, Converted};
+use super::common::{
+    deserialize_frontmatter, find_markdown_files, parse_frontmatter_with_field_info,
+    read_source_file, restore_comments, serialize_frontmatter, with_source_body, write_target,
+    CursorMetadata, WindsurfMetadata, WindsurfTrigger,
+};
+use super::options::ConvertOptions;
+use crate::error::{Result, RulerError};
+
+pub fn convert_windsurf_to_cursor(
+    from_dir: &Path,
+    to_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting Windsurf rules to Cursor rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    // Create target directory if it doesn't exist; check mode writes nothing
+    if !options.check {
+        fs::create_dir_all(to_dir).map_err(|source| RulerError::CreateDir {
+            path: to_dir.to_path_buf(),
+            source,
+        })?;
+    }
+
+    // Find all .md files in the source directory
+    let source_files = find_markdown_files(from_dir, options)?;
+
+    if source_files.is_empty() && options.shows_progress() {
+        println!("No .md files found in {}", from_dir.display());
+    }
+
+    run_batch(
+        from_dir,
+        to_dir,
+        Some(".mdc"),
+        source_files,
+        options,
+        // Change extension from .md to .mdc
+        |relative_path| relative_path.with_extension("mdc"),
+        |source_file, _relative_path, target_path| {
+            let content = read_source_file(source_file, options)?;
+            let output_content = convert_windsurf_content(&content, options)?;
+            let output_content = with_source_body(&content, output_content, options);
+            let written = write_target(target_path, &output_content, options)?;
+            Ok(Converted::target(target_path, written))
+        },
+    )
+}
+
+/// Converts the raw contents of a Windsurf rule into Cursor rule contents.
+///
+/// `trigger: always_on` becomes `alwaysApply: true`; every other trigger is
+/// `alwaysApply: false`, with `globs` carried over for `trigger: glob`.
+pub fn convert_windsurf_content(content: &str, options: &ConvertOptions) -> Result<String> {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content)?;
+
+    let fm = match frontmatter {
+        Some(fm) => fm,
+        None => {
+            debug!("no frontmatter detected");
+            return Ok(body);
+        }
+    };
+
+    debug!("detected {:?} frontmatter:\n{}", field_info.format, fm);
+    let windsurf_meta: WindsurfMetadata =
+        deserialize_frontmatter(&fm, field_info.format, "Windsurf")?;
+    debug!("parsed {:?}", windsurf_meta);
+
+    let mut cursor_meta = CursorMetadata {
+        description: windsurf_meta.description,
+        ..Default::default()
+    };
+    match windsurf_meta.trigger {
+        Some(WindsurfTrigger::AlwaysOn) => {
+            cursor_meta.always_apply = Some(true);
+            cursor_meta.globs = Some(vec![]);
+        }
+        Some(WindsurfTrigger::Glob) => {
+            cursor_meta.always_apply = Some(false);
+            cursor_meta.globs = windsurf_meta.globs;
+        }
+        Some(WindsurfTrigger::Manual | WindsurfTrigger::ModelDecision) => {
+            cursor_meta.always_apply = Some(false);
+        }
+        None => {}
+    }
+    debug!(
+        "trigger: {:?} mapped to alwaysApply: {:?}, globs: {:?}",
+        windsurf_meta.trigger, cursor_meta.always_apply, cursor_meta.globs
+    );
+
+    let format = options.frontmatter_format.unwrap_or(field_info.format);
+    let frontmatter = restore_comments(
+        &serialize_frontmatter(&cursor_meta, format, "Cursor")?,
+        &field_info.comments,
+        &[("trigger", "alwaysApply")],
+    );
+    let delimiter = format.delimiter();
+    let header = field_info
+        .line_ending
+        .apply(&format!("{}\n{}{}\n\n", delimiter, frontmatter, delimiter));
+    Ok(format!("{}{}", header, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert_windsurf_content;
+    use crate::parser::options::ConvertOptions;
+
+    fn convert(input: &str) -> String {
+        convert_windsurf_content(input, &ConvertOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_glob_trigger_becomes_globs() {
+        let output =
+            convert("---\ntrigger: glob\ndescription: \"TS\"\nglobs: \"*.ts,*.tsx\"\n---\n\nBody.");
+        assert_eq!(
+            output,
+            "---\ndescription: TS\nglobs:\n- '*.ts'\n- '*.tsx'\nalwaysApply: false\n---\n\nBody."
+        );
+    }
+
+    #[test]
+    fn test_always_on_and_manual_triggers() {
+        assert!(convert("---\ntrigger: always_on\n---\n\nBody.").contains("alwaysApply: true\n"));
+        let manual = convert("---\ntrigger: manual\n---\n\nBody.");
+        assert!(manual.contains("alwaysApply: false\n"));
+        assert!(!manual.contains("globs"));
+    }
+}
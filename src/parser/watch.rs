@@ -0,0 +1,141 @@
+//! `--watch`: re-converting source files as they change.
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::common::filter_sources;
+use super::options::ConvertOptions;
+use crate::error::Result;
+
+/// How long to wait after an event for more before converting, so an editor
+/// saving a file in several steps triggers one conversion.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches `from_dir` until interrupted, calling `convert` with the source
+/// files changed by each burst of filesystem events.
+///
+/// `is_source` selects the files worth converting by name; `--include` and
+/// `--exclude` apply as for a full run, and files under any of `to_dirs` are
+/// ignored so that targets written inside the source tree don't retrigger.
+/// Errors from `convert` are reported and watching continues.
+pub fn watch_sources<S, C>(
+    from_dir: &Path,
+    to_dirs: &[PathBuf],
+    options: &ConvertOptions,
+    is_source: S,
+    mut convert: C,
+) -> Result<()>
+where
+    S: Fn(&Path) -> bool,
+    C: FnMut(Vec<PathBuf>) -> Result<()>,
+{
+    // Events carry absolute paths; they are mapped back under `from_dir`
+    let watched = from_dir
+        .canonicalize()
+        .unwrap_or_else(|_| from_dir.to_path_buf());
+    let ignored: Vec<PathBuf> = to_dirs
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(&watched, RecursiveMode::Recursive)?;
+    if options.shows_progress() {
+        println!("Watching {} for changes...", from_dir.display());
+    }
+
+    while let Ok(event) = receiver.recv() {
+        let mut events = vec![event];
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+
+        let changed = changed_sources(events, &watched, &ignored, &is_source)
+            .into_iter()
+            .map(|relative| from_dir.join(relative))
+            .collect();
+        let changed = filter_sources(from_dir, changed, options)?;
+        if changed.is_empty() {
+            continue;
+        }
+        if let Err(e) = convert(changed) {
+            eprintln!("Error: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// The created or modified files among `events`, relative to `watched`, in
+// path order and without duplicates.
+fn changed_sources(
+    events: Vec<notify::Result<Event>>,
+    watched: &Path,
+    ignored: &[PathBuf],
+    is_source: &dyn Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    let mut changed = BTreeSet::new();
+    for event in events.into_iter().filter_map(|event| event.ok()) {
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            continue;
+        }
+        for path in event.paths {
+            if !path.is_file()
+                || !is_source(&path)
+                || ignored.iter().any(|dir| path.starts_with(dir))
+            {
+                continue;
+            }
+            if let Ok(relative) = path.strip_prefix(watched) {
+                changed.insert(relative.to_path_buf());
+            }
+        }
+    }
+    changed.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_sources;
+    use notify::event::{CreateKind, ModifyKind, RemoveKind};
+    use notify::{Event, EventKind};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_changed_sources_keeps_modified_rules_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("rules/out")).unwrap();
+        for file in ["a.mdc", "b.mdc", "notes.txt", "out/a.instructions.md"] {
+            fs::write(root.join("rules").join(file), "Body.").unwrap();
+        }
+        let event = |kind, file: &str| Ok(Event::new(kind).add_path(root.join("rules").join(file)));
+
+        let events = vec![
+            event(EventKind::Modify(ModifyKind::Any), "b.mdc"),
+            event(EventKind::Create(CreateKind::File), "a.mdc"),
+            event(EventKind::Modify(ModifyKind::Any), "b.mdc"),
+            event(EventKind::Modify(ModifyKind::Any), "notes.txt"),
+            event(EventKind::Modify(ModifyKind::Any), "out/a.instructions.md"),
+            event(EventKind::Remove(RemoveKind::File), "gone.mdc"),
+        ];
+        let is_source = |path: &Path| {
+            path.extension()
+                .is_some_and(|ext| ext == "mdc" || ext == "md")
+        };
+        let changed = changed_sources(
+            events,
+            &root.join("rules"),
+            &[root.join("rules/out")],
+            &is_source,
+        );
+        assert_eq!(
+            changed,
+            vec![PathBuf::from("a.mdc"), PathBuf::from("b.mdc")]
+        );
+    }
+}
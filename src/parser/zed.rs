@@ -0,0 +1,104 @@
+//! Conversion between Cursor rules and Zed's `.rules` project rules file.
+//!
+//! Zed's assistant includes the `.rules` file at the project root in every
+//! conversation. It is plain Markdown without frontmatter, so it only holds
+//! Cursor's `alwaysApply: true` rules, and a rule's description survives as
+//! its section heading.
+
+use std::path::Path;
+
+use super::claude::{always_applied_section, convert_claude_content, convert_instructions_file};
+use super::merge::merge_into_file;
+use super::options::ConvertOptions;
+use crate::error::Result;
+
+/// Default path of Zed's project rules file.
+pub const ZED_FILE: &str = ".rules";
+
+/// File name of the rule `zed2c` writes.
+pub const ZED_RULE: &str = "zed.mdc";
+
+/// Concatenates every `alwaysApply: true` rule in `from_dir` into the Zed
+/// rules file `target`, one `## <title>` section per rule, ordered like
+/// `c2claude`. Rules scoped to globs or applied on request are left out, as
+/// Zed has no way to activate them.
+pub fn convert_cursor_to_zed(
+    from_dir: &Path,
+    target: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting always-applied Cursor rules to Zed rules...");
+        println!("From: {}", from_dir.display());
+        println!("To: {}", target.display());
+    }
+
+    merge_into_file(from_dir, target, options, |source_file, relative| {
+        always_applied_section(source_file, relative, options)
+    })
+}
+
+/// Converts the Zed rules file `source` into the always-applied Cursor rule
+/// `zed.mdc` in `to_dir`.
+pub fn convert_zed_to_cursor(source: &Path, to_dir: &Path, options: &ConvertOptions) -> Result<()> {
+    if options.shows_progress() {
+        println!("Converting Zed rules to a Cursor rule...");
+        println!("From: {}", source.display());
+        println!("To: {}", to_dir.display());
+    }
+
+    convert_instructions_file(
+        source,
+        &to_dir.join(ZED_RULE),
+        ZED_FILE,
+        convert_zed_content,
+        options,
+    )
+}
+
+/// Converts the raw contents of a Zed rules file into an always-applied
+/// Cursor rule, the same way as a `CLAUDE.md`: the body is kept as is and its
+/// first `# ` heading, if any, becomes the description.
+pub fn convert_zed_content(content: &str) -> Result<String> {
+    convert_claude_content(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_cursor_to_zed, convert_zed_content};
+    use crate::parser::options::ConvertOptions;
+    use std::fs;
+
+    #[test]
+    fn test_only_always_applied_rules_go_into_zed_rules() {
+        let temp = tempfile::tempdir().unwrap();
+        let from_dir = temp.path().join("rules");
+        fs::create_dir_all(&from_dir).unwrap();
+        fs::write(
+            from_dir.join("style.mdc"),
+            "---\ndescription: Style\nalwaysApply: true\n---\n\nBe concise.",
+        )
+        .unwrap();
+        fs::write(
+            from_dir.join("rust.mdc"),
+            "---\ndescription: Rust\nglobs: [\"*.rs\"]\n---\n\nUse clippy.",
+        )
+        .unwrap();
+
+        let target = temp.path().join(".rules");
+        convert_cursor_to_zed(&from_dir, &target, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "## Style\n\nBe concise.\n"
+        );
+    }
+
+    #[test]
+    fn test_zed_rules_become_an_always_applied_rule() {
+        let output = convert_zed_content("# Project rules\n\nRun `cargo test` first.\n").unwrap();
+        assert_eq!(
+            output,
+            "---\ndescription: Project rules\nalwaysApply: true\n---\n\n# Project rules\n\nRun `cargo test` first."
+        );
+    }
+}
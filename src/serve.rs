@@ -0,0 +1,154 @@
+//! A minimal local HTTP server previewing Cursor rules next to their
+//! GitHub Copilot conversion.
+
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+
+use crate::parser::common::find_cursor_files;
+use crate::parser::defaults::read_cursor_rule;
+use crate::parser::{convert_cursor_content_with_options, ConvertOptions};
+
+/// Binds `127.0.0.1:<port>` and serves previews of the rules in `from_dir` until interrupted.
+pub fn serve(from_dir: &Path, port: u16, options: &ConvertOptions) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Failed to bind to port {}", port))?;
+    println!(
+        "Previewing {} at http://{}",
+        from_dir.display(),
+        listener.local_addr()?
+    );
+    serve_on(listener, from_dir, options)
+}
+
+/// Serves previews on an already-bound listener, one request at a time.
+pub fn serve_on(listener: TcpListener, from_dir: &Path, options: &ConvertOptions) -> Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream.context("Failed to accept connection")?;
+        if let Err(e) = handle_connection(stream, from_dir, options) {
+            eprintln!("Error serving request: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    from_dir: &Path,
+    options: &ConvertOptions,
+) -> Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = if path == "/" {
+        match render_preview(from_dir, options) {
+            Ok(html) => ("200 OK", html),
+            Err(e) => (
+                "500 Internal Server Error",
+                format!("<pre>{}</pre>", escape_html(&format!("{:#}", e))),
+            ),
+        }
+    } else {
+        ("404 Not Found", "<p>Not found</p>".to_string())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Renders every rule in `from_dir` beside its would-be GitHub instruction.
+pub fn render_preview(from_dir: &Path, options: &ConvertOptions) -> Result<String> {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>ruler preview</title>\
+         <style>table{width:100%;border-collapse:collapse}td{vertical-align:top;width:50%;\
+         border:1px solid #ccc}pre{white-space:pre-wrap;margin:0.5em}</style></head><body>\n",
+    );
+    writeln!(
+        html,
+        "<h1>{}</h1>",
+        escape_html(&from_dir.display().to_string())
+    )?;
+
+    for source in find_cursor_files(from_dir, options)? {
+        let relative = source.strip_prefix(from_dir).unwrap_or(&source);
+        let converted = read_cursor_rule(&source, options).and_then(|content| {
+            let output = convert_cursor_content_with_options(&content, options, Some(relative))?;
+            Ok((content, output))
+        });
+
+        writeln!(
+            html,
+            "<h2>{}</h2>",
+            escape_html(&relative.display().to_string())
+        )?;
+        match converted {
+            Ok((content, output)) => writeln!(
+                html,
+                "<table><tr><td><pre>{}</pre></td><td><pre>{}</pre></td></tr></table>",
+                escape_html(&content),
+                escape_html(&output)
+            )?,
+            Err(e) => writeln!(
+                html,
+                "<p>Error converting: {}</p>",
+                escape_html(&format!("{:#}", e))
+            )?,
+        }
+    }
+
+    html.push_str("</body></html>\n");
+    Ok(html)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::serve_on;
+    use crate::parser::ConvertOptions;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_serve_renders_conversion() {
+        let temp = tempfile::tempdir().unwrap();
+        let rules = temp.path().to_path_buf();
+        fs::write(
+            rules.join("style.mdc"),
+            "---\ndescription: \"Style\"\nglobs: \"*.rs\"\n---\n\nUse <Result>.",
+        )
+        .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve_on(listener, &rules, &ConvertOptions::default()));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("<h2>style.mdc</h2>"));
+        assert!(response.contains("applyTo: &quot;*.rs&quot;"));
+        assert!(response.contains("Use &lt;Result&gt;."));
+    }
+}
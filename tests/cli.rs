@@ -0,0 +1,578 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+fn ruler() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_ruler"))
+}
+
+/// `ruler <args> --from <from> --to <to>`.
+fn command(from: &Path, to: &Path, args: &[&str]) -> Command {
+    let mut command = ruler();
+    command
+        .args(args)
+        .arg("--from")
+        .arg(from)
+        .arg("--to")
+        .arg(to);
+    command
+}
+
+fn run(from: &Path, to: &Path, args: &[&str]) -> Output {
+    command(from, to, args).output().unwrap()
+}
+
+/// Writes each `(path, contents)` file under `dir`, creating its parents.
+fn write_files(dir: &Path, files: &[(&str, &str)]) {
+    std::fs::create_dir_all(dir).unwrap();
+    for (path, contents) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+/// A temporary directory with the given rules in its `rules` subdirectory,
+/// returned along with that directory and an `out` directory beside it.
+fn setup(rules: &[(&str, &str)]) -> (TempDir, PathBuf, PathBuf) {
+    let temp = tempfile::tempdir().unwrap();
+    let rules_dir = temp.path().join("rules");
+    let out = temp.path().join("out");
+    write_files(&rules_dir, rules);
+    (temp, rules_dir, out)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn test_stdin_c2g_writes_to_stdout() {
+    let mut child = ruler()
+        .args(["c2g", "--stdin", "--from", "ignored", "--to", "ignored"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"---\ndescription: \"Piped\"\nglobs: \"*.rs\"\n---\n\nBody.")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "---\ndescription: \"Piped\"\napplyTo: \"*.rs\"\n---\n\nBody.\n"
+    );
+    assert!(!Path::new("ignored").exists());
+}
+
+#[test]
+fn test_stdin_reports_parse_errors() {
+    let mut child = ruler()
+        .args(["g2c", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"---\napplyTo: [unclosed\n---\n\nBody.")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Failed to parse GitHub frontmatter"));
+}
+
+#[test]
+fn test_c2g_uses_alternate_root_when_default_is_absent() {
+    let temp = tempfile::tempdir().unwrap();
+    write_files(
+        temp.path(),
+        &[(
+            ".ai/rules/style.mdc",
+            "---\ndescription: \"Style\"\n---\n\nBody.",
+        )],
+    );
+
+    let output = ruler()
+        .arg("c2g")
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("using rules root .ai/rules"));
+    assert!(temp
+        .path()
+        .join(".github/instructions/style.instructions.md")
+        .exists());
+}
+
+#[test]
+fn test_check_reports_stale_targets_without_writing() {
+    let (_temp, rules, out) = setup(&[("a.mdc", "---\ndescription: \"A\"\n---\n\nNew body.")]);
+
+    let output = run(&rules, &out, &["c2g", "--check"]);
+    assert!(!output.status.success());
+    assert!(!out.exists());
+
+    assert!(run(&rules, &out, &["c2g"]).status.success());
+    assert!(run(&rules, &out, &["c2g", "--check"]).status.success());
+
+    write_files(
+        &rules,
+        &[("a.mdc", "---\ndescription: \"A\"\n---\n\nEdited body.")],
+    );
+    let output = run(&rules, &out, &["c2g", "--check"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("\n-New body."));
+    assert!(stderr.contains("\n+Edited body."));
+    assert_eq!(
+        std::fs::read_to_string(out.join("a.instructions.md")).unwrap(),
+        "---\ndescription: \"A\"\n---\n\nNew body.\n"
+    );
+}
+
+#[test]
+fn test_fail_on_warning_exits_nonzero_after_converting() {
+    let (_temp, rules, out) = setup(&[("abs.mdc", "---\nglobs: \"/src/**\"\n---\n\nBody.")]);
+
+    assert!(run(&rules, &out, &["c2g"]).status.success());
+
+    let output = run(&rules, &out, &["c2g", "--fail-on-warning"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("is an absolute path"));
+    assert!(stderr.contains("1 warning(s) emitted with --fail-on-warning"));
+    assert!(out.join("abs.instructions.md").exists());
+}
+
+#[test]
+fn test_verbose_logs_field_mapping() {
+    let (_temp, rules, out) = setup(&[(
+        "a.mdc",
+        "---\nglobs: [\"*.ts\"]\nalwaysApply: true\n---\n\nBody.",
+    )]);
+
+    let output = run(&rules, &out, &["c2g", "--verbose"]);
+    assert!(output.status.success());
+    let log = stderr(&output);
+    assert!(log.contains("[debug] detected Yaml frontmatter:"));
+    assert!(log.contains("alwaysApply: true overrides globs [\"*.ts\"]; applyTo: \"**\""));
+
+    let output = run(&rules, &out, &["c2g"]);
+    assert!(output.status.success());
+    assert!(!stderr(&output).contains("[debug]"));
+}
+
+#[test]
+fn test_colliding_targets_are_reported_with_both_sources() {
+    let (_temp, rules, out) = setup(&[
+        ("style.md", "Markdown style."),
+        ("style.mdc", "Cursor style."),
+        ("other.mdc", "Other."),
+    ]);
+
+    let stderr = stderr(&run(&rules, &out, &["c2g"]));
+    assert!(stderr.contains("2 sources map to"));
+    assert!(stderr.contains(&rules.join("style.md").display().to_string()));
+    assert!(stderr.contains(&rules.join("style.mdc").display().to_string()));
+    assert!(!out.join("style.instructions.md").exists());
+    assert!(out.join("other.instructions.md").exists());
+}
+
+#[test]
+fn test_latin1_rule_is_skipped_and_counted_separately() {
+    let (_temp, rules, out) = setup(&[("other.mdc", "Other.")]);
+    // "Café" in latin-1: 0xE9 on its own is not valid UTF-8
+    std::fs::write(
+        rules.join("latin1.mdc"),
+        b"---\ndescription: Caf\xe9\n---\n\nBody.",
+    )
+    .unwrap();
+
+    let output = run(&rules, &out, &["c2g"]);
+    assert!(stderr(&output).contains(&format!(
+        "File is not valid UTF-8 (invalid byte at offset 20), skipping: {}",
+        rules.join("latin1.mdc").display()
+    )));
+    assert!(stdout(&output).contains(
+        "Conversion completed with 1 successes, 0 errors and 1 non-UTF-8 file(s) skipped."
+    ));
+    assert!(!out.join("latin1.instructions.md").exists());
+    assert!(out.join("other.instructions.md").exists());
+}
+
+#[test]
+fn test_quiet_prints_only_the_summary() {
+    let (_temp, rules, out) = setup(&[
+        ("a.mdc", "---\nglobs: \"*.rs\"\n---\n\nA."),
+        ("broken.mdc", "---\nglobs: [unclosed\n---\n\nB."),
+    ]);
+
+    let output = run(&rules, &out, &["c2g", "--quiet"]);
+    assert_eq!(
+        stdout(&output),
+        "Conversion completed with 1 successes and 1 errors.\n"
+    );
+    assert!(stderr(&output).contains("Error converting"));
+    assert!(out.join("a.instructions.md").exists());
+
+    let conflict = ruler().args(["c2g", "-q", "-v"]).output().unwrap();
+    assert!(!conflict.status.success());
+}
+
+#[test]
+fn test_config_file_supplies_defaults_that_flags_override() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    std::fs::create_dir_all(root.join("sub/dir")).unwrap();
+    write_files(
+        root,
+        &[
+            ("rules/style.mdc", "Style."),
+            ("rules/wip.draft.mdc", "Draft."),
+            (
+                "ruler.toml",
+                "exclude = [\"*.draft.mdc\"]\n\n[c2g]\nfrom = \"rules\"\nto = \"from-config\"\n",
+            ),
+        ],
+    );
+
+    // Found from a subdirectory; paths are relative to the config file
+    let output = ruler()
+        .arg("c2g")
+        .current_dir(root.join("sub/dir"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(root.join("from-config/style.instructions.md").exists());
+    assert!(!root.join("from-config/wip.draft.instructions.md").exists());
+
+    let output = ruler()
+        .args(["c2g", "--to", "from-flag", "--exclude", "style.mdc"])
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!root.join("from-flag/style.instructions.md").exists());
+    assert!(root.join("from-flag/wip.draft.instructions.md").exists());
+}
+
+#[test]
+fn test_strict_aborts_at_the_first_broken_rule() {
+    let (_temp, rules, out) = setup(&[
+        ("a.mdc", "A."),
+        ("b.mdc", "---\nglobs: [unclosed\n---\n\nB."),
+        ("c.mdc", "C."),
+    ]);
+
+    // Without --strict the other rules are still converted
+    assert_eq!(
+        run(&rules, &out, &["c2g", "-j", "1"]).status.code(),
+        Some(1)
+    );
+    assert!(out.join("c.instructions.md").exists());
+    std::fs::remove_dir_all(&out).unwrap();
+
+    let output = run(&rules, &out, &["c2g", "-j", "1", "--strict"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains(
+        "Conversion aborted with --strict after 1 successes, 1 errors and 0 warnings; 1 file(s) skipped."
+    ));
+    assert!(stderr(&output).contains("Aborted by --strict: 1 error(s) and 0 warning(s)"));
+    assert!(out.join("a.instructions.md").exists());
+    assert!(!out.join("c.instructions.md").exists());
+}
+
+#[test]
+fn test_c2g_fans_out_to_every_target_directory() {
+    let (temp, rules, _out) = setup(&[
+        ("style.mdc", "Style."),
+        ("broken.mdc", "---\nglobs: [unclosed\n---\n\nB."),
+    ]);
+
+    let output = command(&rules, &temp.path().join("github"), &["c2g"])
+        .arg("--to")
+        .arg(temp.path().join("other"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = stdout(&output);
+    assert_eq!(
+        stdout
+            .matches("Conversion completed with 1 successes and 1 errors.")
+            .count(),
+        1
+    );
+    assert!(stdout.contains(&format!("To: {}", temp.path().join("other").display())));
+    assert!(temp.path().join("github/style.instructions.md").exists());
+    assert!(temp.path().join("other/style.instructions.md").exists());
+
+    // The other modes write a single tree
+    let output = ruler()
+        .args(["g2c", "--to", "a", "--to", "b"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--to can only be given more than once for c2g"));
+}
+
+#[test]
+fn test_exit_codes_distinguish_failures_from_usage_errors() {
+    let (_temp, rules, out) = setup(&[("a.mdc", "A.")]);
+    let code = |args: &[&str]| run(&rules, &out, args).status.code();
+
+    assert_eq!(code(&["c2g"]), Some(0));
+
+    write_files(
+        &rules,
+        &[("broken.mdc", "---\nglobs: [unclosed\n---\n\nB.")],
+    );
+    assert_eq!(code(&["c2g"]), Some(1));
+    assert!(out.join("a.instructions.md").exists());
+
+    assert_eq!(code(&["c2g", "--no-such-flag"]), Some(2));
+    assert_eq!(code(&["g2c", "--watch"]), Some(2));
+    assert_eq!(code(&["c2g", "--target-extension", "md"]), Some(2));
+}
+
+#[test]
+fn test_color_never_writes_no_escape_codes() {
+    let (_temp, rules, out) = setup(&[
+        ("a.mdc", "A."),
+        ("broken.mdc", "---\nglobs: [unclosed\n---\n\nB."),
+    ]);
+
+    let output = run(&rules, &out, &["c2g", "--color", "never"]);
+    let (stdout_text, stderr_text) = (stdout(&output), stderr(&output));
+    assert!(stdout_text.contains("a.instructions.md"));
+    assert!(stderr_text.contains("broken.mdc"));
+    assert!(!stdout_text.contains('\x1b') && !stderr_text.contains('\x1b'));
+
+    let output = run(&rules, &out, &["c2g", "--color", "always"]);
+    assert!(stdout(&output).contains("\x1b[32m"));
+    assert!(stderr(&output).contains("\x1b[31m"));
+}
+
+#[test]
+fn test_expand_env_fills_bodies_and_fails_on_unset_variables_under_strict() {
+    let (_temp, rules, out) = setup(&[(
+        "a.mdc",
+        "---\nglobs: \"*.rs\"\n---\n\nWelcome to ${PROJECT_NAME}${RULER_UNSET}.",
+    )]);
+    let run = |args: &[&str]| {
+        command(&rules, &out, args)
+            .env("PROJECT_NAME", "Ruler")
+            .env_remove("RULER_UNSET")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["c2g", "--expand-env"]);
+    assert!(output.status.success());
+    assert!(stderr(&output).contains("`${RULER_UNSET}` is not set"));
+    assert_eq!(
+        std::fs::read_to_string(out.join("a.instructions.md")).unwrap(),
+        "---\napplyTo: \"*.rs\"\n---\n\nWelcome to Ruler${RULER_UNSET}.\n"
+    );
+
+    let output = run(&["c2g", "--expand-env", "--strict"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Environment variable `RULER_UNSET` is not set"));
+}
+
+#[test]
+fn test_missing_source_dir_is_reported_before_walking() {
+    let temp = tempfile::tempdir().unwrap();
+    let missing = temp.path().join("nonexistent");
+    let out = temp.path().join("out");
+
+    for mode in ["c2g", "g2c"] {
+        let output = run(&missing, &out, &[mode]);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = stderr(&output);
+        assert!(stderr.contains(&format!(
+            "source directory does not exist: {}",
+            missing.display()
+        )));
+        assert!(!stderr.contains("Failed to read directory entry"));
+        assert!(!out.exists());
+    }
+}
+
+#[test]
+fn test_frontmatter_only_keeps_body_bytes() {
+    let body = "\n\n  Indented first line.  \n\tTabbed line \t\n\n\n";
+    let rule = format!("---\nglobs: \"*.rs\"\n---{}", body);
+    let (_temp, rules, out) = setup(&[("a.mdc", &rule)]);
+
+    let output = run(&rules, &out, &["c2g", "--frontmatter-only"]);
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read(out.join("a.instructions.md")).unwrap(),
+        format!("---\napplyTo: \"*.rs\"\n---{}", body).into_bytes()
+    );
+}
+
+#[test]
+fn test_diff_prints_what_c2g_would_change() {
+    let stale = "---\napplyTo: \"*.rs\"\n---\n\nUse rustfmt.\n";
+    let (temp, rules, out) = setup(&[
+        ("a.mdc", "---\nglobs: \"*.rs\"\n---\n\nUse clippy."),
+        ("b.mdc", "---\nalwaysApply: true\n---\n\nBe brief."),
+    ]);
+    write_files(&out, &[("a.instructions.md", stale)]);
+
+    let output = run(&rules, &out, &["diff", "c2g"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("\n-Use rustfmt.\n+Use clippy.\n"));
+    assert!(stdout.contains("--- /dev/null\n"));
+    assert!(stdout.contains("\n+applyTo: \"**\"\n"));
+    assert!(stdout.contains("2 target(s) would change."));
+    assert_eq!(
+        std::fs::read_to_string(out.join("a.instructions.md")).unwrap(),
+        stale
+    );
+    assert!(!out.join("b.instructions.md").exists());
+
+    // Modes that don't convert would write, and diffs would break the JSON report
+    let output = ruler()
+        .args(["diff", "init", "--project-root"])
+        .arg(temp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!temp.path().join(".cursor").exists());
+    let output = run(&rules, &out, &["diff", "c2g", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_report_only_errors_prints_no_success_lines() {
+    let (_temp, rules, out) = setup(&[
+        ("good.mdc", "---\nglobs: \"*.rs\"\n---\n\nFine."),
+        ("bad.mdc", "---\nglobs: [unclosed\n---\n\nBroken."),
+    ]);
+
+    let output = run(&rules, &out, &["c2g", "--report-only-errors"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = stdout(&output);
+    assert!(!stdout.contains("Converted:"));
+    assert!(!stdout.contains("Converting Cursor rules"));
+    assert!(stdout.contains("Conversion completed with 1 successes and 1 errors."));
+    assert!(stdout.contains(&format!("Failed:\n  {}: ", rules.join("bad.mdc").display())));
+    assert!(stderr(&output).contains("Error converting"));
+    assert!(out.join("good.instructions.md").exists());
+}
+
+#[test]
+fn test_prune_deletes_targets_of_removed_sources() {
+    let (temp, rules, out) = setup(&[
+        ("kept.mdc", "---\nglobs: \"*.rs\"\n---\n\nKept."),
+        ("excluded.mdc", "Excluded."),
+        ("old/renamed.mdc", "Renamed."),
+    ]);
+    write_files(&out, &[("hand.instructions.md", "Hand-written.")]);
+    let manifest = temp.path().join("manifest.json");
+    let manifest = manifest.to_str().unwrap();
+    assert!(run(&rules, &out, &["c2g", "--manifest", manifest])
+        .status
+        .success());
+
+    std::fs::remove_file(rules.join("old/renamed.mdc")).unwrap();
+    let args = [
+        "c2g",
+        "--manifest",
+        manifest,
+        "--prune",
+        "--exclude",
+        "excluded.mdc",
+    ];
+    let output = run(&rules, &out, &args);
+    assert!(output.status.success());
+    let orphan = out.join("old/renamed.instructions.md");
+    assert!(stdout(&output).contains(&format!("Pruned: {}", orphan.display())));
+    assert!(!orphan.exists());
+    assert!(out.join("kept.instructions.md").exists());
+    assert!(out.join("excluded.instructions.md").exists());
+    assert!(out.join("hand.instructions.md").exists());
+}
+
+#[test]
+fn test_prune_runs_after_the_last_source_is_removed() {
+    let (temp, rules, out) = setup(&[("a.mdc", "A.")]);
+    let manifest = temp.path().join("manifest.json");
+    let args = ["c2g", "--manifest", manifest.to_str().unwrap(), "--prune"];
+    assert!(run(&rules, &out, &args).status.success());
+    assert!(out.join("a.instructions.md").exists());
+
+    std::fs::remove_file(rules.join("a.mdc")).unwrap();
+    let output = run(&rules, &out, &args);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("No .mdc or .md files found"));
+    assert!(!out.join("a.instructions.md").exists());
+}
+
+#[test]
+fn test_prune_keeps_hand_written_cursor_rules() {
+    let (temp, github, rules) = setup(&[("style.instructions.md", "Style.")]);
+    write_files(&rules, &[("hand.mdc", "Hand-written.")]);
+    let manifest = temp.path().join("manifest.json");
+
+    let args = ["g2c", "--prune", "--manifest", manifest.to_str().unwrap()];
+    let output = run(&github, &rules, &args);
+    assert!(output.status.success());
+    assert!(!stdout(&output).contains("Pruned:"));
+    assert!(rules.join("style.mdc").exists());
+    assert!(rules.join("hand.mdc").exists());
+
+    let output = ruler().args(["g2c", "--prune"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_json_report_stays_valid_with_a_changelog() {
+    let (temp, rules, out) = setup(&[("style.mdc", "Style.")]);
+    let manifest = temp.path().join("manifest.json");
+    let empty = r#"{"conversions": [], "success_count": 0, "error_count": 0}"#;
+    std::fs::write(&manifest, empty).unwrap();
+
+    let args = [
+        "c2g",
+        "--format",
+        "json",
+        "--changelog-against",
+        manifest.to_str().unwrap(),
+    ];
+    let output = run(&rules, &out, &args);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["success_count"], 1);
+    assert!(stderr(&output).contains("style.instructions.md"));
+}
+
+#[test]
+fn test_always_apply_glob_must_be_a_pattern() {
+    let (_temp, rules, out) = setup(&[]);
+    for glob in ["", "  ", "src/[abc"] {
+        let output = run(&rules, &out, &["c2g", "--always-apply-glob", glob]);
+        assert_eq!(output.status.code(), Some(2), "{:?}", glob);
+        assert!(!out.exists());
+    }
+}
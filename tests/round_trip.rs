@@ -0,0 +1,94 @@
+//! Runs every Cursor rule in the fixture corpus through c2g and then g2c and
+//! checks that the rule means the same thing afterwards. The fields README.md
+//! lists as lossy are normalized away before comparing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ruler::parser::common::{parse_cursor_metadata, parse_frontmatter_with_field_info};
+use ruler::parser::{convert_cursor_content, convert_github_content};
+
+const CORPUS: &[&str] = &["fixtures/cursor", "fixtures/round-trip", "examples"];
+
+/// What a Cursor rule means, independent of how its frontmatter is written.
+#[derive(Debug, PartialEq)]
+struct Semantics {
+    description: Option<String>,
+    globs: Vec<String>,
+    always_apply: bool,
+    name: Option<String>,
+    authors: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    version: Option<String>,
+    priority: Option<i64>,
+    body: String,
+}
+
+fn semantics(content: &str) -> Semantics {
+    let (frontmatter, body, field_info) = parse_frontmatter_with_field_info(content).unwrap();
+    let meta = frontmatter
+        .map(|fm| parse_cursor_metadata(&fm, field_info.format).unwrap())
+        .unwrap_or_default();
+    let always_apply = meta.always_apply.unwrap_or(false);
+    Semantics {
+        // An empty description means the same as none
+        description: meta.description.filter(|d| !d.is_empty()),
+        // Globs are ignored by Cursor once a rule is always applied
+        globs: if always_apply {
+            Vec::new()
+        } else {
+            meta.globs.unwrap_or_default()
+        },
+        always_apply,
+        name: meta.name,
+        authors: meta.authors,
+        tags: meta.tags,
+        version: meta.version,
+        priority: meta.priority,
+        body,
+    }
+}
+
+fn corpus() -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                walk(&path, files);
+            } else if path.extension().is_some_and(|ext| ext == "mdc") {
+                files.push(path);
+            }
+        }
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut files = Vec::new();
+    for dir in CORPUS {
+        walk(&root.join(dir), &mut files);
+    }
+    files.sort();
+    files
+}
+
+#[test]
+fn test_c2g_then_g2c_preserves_rule_semantics() {
+    let files = corpus();
+    assert!(files.len() >= 10, "corpus not found: {:?}", files);
+
+    for path in files {
+        let original = fs::read_to_string(&path).unwrap();
+        let github = convert_cursor_content(&original).unwrap();
+        let restored = convert_github_content(&github).unwrap();
+        assert_eq!(
+            semantics(&restored),
+            semantics(&original),
+            "{} changed meaning after c2g -> g2c:\n{}",
+            path.display(),
+            restored
+        );
+
+        // Once converted, another round trip is a no-op
+        let again = convert_github_content(&convert_cursor_content(&restored).unwrap()).unwrap();
+        assert_eq!(again, restored, "{} is not stable", path.display());
+    }
+}